@@ -15,9 +15,7 @@ pub fn wallet_button() -> Html {
     };
 
     let label = if ethereum.is_connected() {
-        if let Some(acc) =
-            ethereum.accounts().expect("Missing accounts! It's disconnected!").first()
-        {
+        if let Some(acc) = ethereum.active_account() {
             format!("{acc}")
         } else {
             "Account missing".into()
@@ -11,10 +11,8 @@ abigen!(TokenContract, "abi/ERC20.json");
 async fn transfer(ethereum: &UseEthereum, token_address: H160, to: H160, amount: U256) {
     let erc20_contract = TokenContract::new(token_address, ethereum.provider().into());
     info!("Trying to execute transaction...");
-    if let Some(account) =
-        ethereum.accounts().expect("Missing accounts! It's disconnected!").first()
-    {
-        if let Ok(tx) = erc20_contract.transfer(to, amount).from(*account).send().await {
+    if let Some(account) = ethereum.active_account() {
+        if let Ok(tx) = erc20_contract.transfer(to, amount).from(account).send().await {
             info!("Transaction commited, awaiting blockchain verification");
             match tx.await {
                 Ok(_) => info!("Token transfered"),
@@ -75,11 +75,9 @@ pub fn signature_button() -> Html {
                 let data = typed_data_for_document("Some Document");
                 let ethereum = ethereum.clone();
                 spawn_local(async move {
-                    if let Some(address) =
-                        ethereum.accounts().expect("Missing accounts! It's disconnected!").first()
-                    {
+                    if let Some(address) = ethereum.active_account() {
                         let signature_res =
-                            ethereum.sign_typed_data(json!(data).to_string(), address).await;
+                            ethereum.sign_typed_data(json!(data).to_string(), &address).await;
 
                         // Checking signature
                         if let Ok(signature_res) = signature_res {
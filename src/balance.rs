@@ -0,0 +1,131 @@
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+    },
+};
+
+use ethers::{
+    providers::JsonRpcClient,
+    types::{Address, Bytes, U256},
+};
+use futures::{stream, Stream};
+use serde_json::json;
+use tokio::sync::watch;
+
+/// `balanceOf(address)` selector of ERC-20 token
+const BALANCE_OF_SELECTOR: [u8; 4] = [0x70, 0xa0, 0x82, 0x31];
+
+/// Native and ERC-20 token balances of an account
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Balances {
+    pub account: Option<Address>,
+    pub chain_id: Option<u64>,
+    pub native: Option<U256>,
+    pub tokens: BTreeMap<Address, U256>,
+}
+
+impl Balances {
+    /// Returns balance of given ERC-20 token
+    pub fn token(&self, token: &Address) -> Option<U256> {
+        self.tokens.get(token).copied()
+    }
+}
+
+/// Keeps track of watched tokens and the latest known balances
+#[derive(Clone, Debug)]
+pub(crate) struct BalanceWatcher {
+    enabled: Arc<AtomicBool>,
+    tokens: Arc<RwLock<Vec<Address>>>,
+    balances: Arc<watch::Sender<Balances>>,
+}
+
+impl BalanceWatcher {
+    pub fn new(enabled: bool, tokens: Vec<Address>) -> Self {
+        let (sender, _) = watch::channel(Balances::default());
+        Self {
+            enabled: Arc::new(AtomicBool::new(enabled || !tokens.is_empty())),
+            tokens: Arc::new(RwLock::new(tokens)),
+            balances: Arc::new(sender),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub fn enable(&self) {
+        self.enabled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn tokens(&self) -> Vec<Address> {
+        self.tokens.read().map(|t| t.clone()).unwrap_or_default()
+    }
+
+    pub fn add_token(&self, token: Address) {
+        self.enable();
+        if let Ok(mut tokens) = self.tokens.write() {
+            if !tokens.contains(&token) {
+                tokens.push(token);
+            }
+        }
+    }
+
+    pub fn remove_token(&self, token: &Address) {
+        if let Ok(mut tokens) = self.tokens.write() {
+            tokens.retain(|t| t != token);
+        }
+    }
+
+    pub fn current(&self) -> Balances {
+        self.balances.borrow().clone()
+    }
+
+    /// Stores new balances. Returns `true` if they differ from previously known ones.
+    pub fn update(&self, balances: Balances) -> bool {
+        self.balances.send_if_modified(|current| {
+            if *current != balances {
+                *current = balances;
+                true
+            } else {
+                false
+            }
+        })
+    }
+
+    /// Stream of balance updates, starting with the latest known balances
+    pub fn subscribe(&self) -> impl Stream<Item = Balances> {
+        let mut receiver = self.balances.subscribe();
+        receiver.mark_changed();
+        stream::unfold(receiver, |mut receiver| async move {
+            receiver.changed().await.ok()?;
+            let balances = receiver.borrow_and_update().clone();
+            Some((balances, receiver))
+        })
+    }
+}
+
+/// Fetches native balance and balances of given ERC-20 tokens
+pub(crate) async fn fetch_balances<C: JsonRpcClient>(
+    client: &C,
+    account: Address,
+    tokens: &[Address],
+) -> Result<(U256, BTreeMap<Address, U256>), C::Error> {
+    let native: U256 = client.request("eth_getBalance", (account, "latest")).await?;
+
+    let mut balances = BTreeMap::new();
+    for token in tokens {
+        let data: Bytes =
+            [&BALANCE_OF_SELECTOR[..], &[0u8; 12], account.as_bytes()].concat().into();
+        let result: Bytes =
+            client.request("eth_call", (json!({ "to": token, "data": data }), "latest")).await?;
+
+        // Contracts that are not ERC-20 tokens might respond with empty data
+        if result.len() >= 32 {
+            balances.insert(*token, U256::from_big_endian(&result[..32]));
+        }
+    }
+
+    Ok((native, balances))
+}
@@ -0,0 +1,255 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use ethers::{
+    providers::JsonRpcClient,
+    types::{Block, H256, U256, U64},
+};
+use futures::{channel::oneshot, stream, Stream};
+use gloo::events::EventListener;
+use tokio::sync::watch;
+use wasm_bindgen::JsValue;
+
+/// Shortest delay between block number polls
+pub const MIN_POLL_INTERVAL_MS: u32 = 2_000;
+/// Longest delay between block number polls
+pub const MAX_POLL_INTERVAL_MS: u32 = 30_000;
+/// Number of block gaps averaged to estimate the chain's block time
+pub const BLOCK_TIME_SAMPLES: usize = 8;
+
+/// Header data of newly observed block
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockHeader {
+    pub number: u64,
+    pub hash: Option<H256>,
+    pub parent_hash: H256,
+    pub timestamp: U256,
+    pub gas_used: U256,
+    pub gas_limit: U256,
+    pub base_fee_per_gas: Option<U256>,
+}
+
+impl From<&Block<H256>> for BlockHeader {
+    fn from(block: &Block<H256>) -> Self {
+        Self {
+            number: block.number.unwrap_or_default().as_u64(),
+            hash: block.hash,
+            parent_hash: block.parent_hash,
+            timestamp: block.timestamp,
+            gas_used: block.gas_used,
+            gas_limit: block.gas_limit,
+            base_fee_per_gas: block.base_fee_per_gas,
+        }
+    }
+}
+
+/// Poll interval adapting to the chain's block time. It follows the average gap between
+/// timestamps of recently observed blocks, staying within given limits.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PollInterval {
+    min: u32,
+    max: u32,
+    timestamps: VecDeque<u64>,
+}
+
+impl PollInterval {
+    pub fn new(min: u32, max: u32) -> Self {
+        Self { min, max, timestamps: VecDeque::with_capacity(BLOCK_TIME_SAMPLES + 1) }
+    }
+
+    /// Average block gap in milliseconds, or the shortest delay until two blocks were observed
+    pub fn current(&self) -> u32 {
+        let (Some(first), Some(last)) = (self.timestamps.front(), self.timestamps.back()) else {
+            return self.min;
+        };
+        let gaps = self.timestamps.len() as u64 - 1;
+        if gaps == 0 {
+            return self.min;
+        }
+
+        let average = (last - first) * 1_000 / gaps;
+        u32::try_from(average).unwrap_or(u32::MAX).clamp(self.min, self.max)
+    }
+
+    /// Records timestamp of newly observed block
+    pub fn on_new_block(&mut self, timestamp: u64) {
+        if self.timestamps.back().is_some_and(|last| *last >= timestamp) {
+            // Chain has changed or reorganized, older samples are not relevant anymore
+            self.timestamps.clear();
+        }
+        self.timestamps.push_back(timestamp);
+        if self.timestamps.len() > BLOCK_TIME_SAMPLES + 1 {
+            self.timestamps.pop_front();
+        }
+    }
+
+    /// Forgets observed blocks, i.e. when the chain has changed
+    pub fn reset(&mut self) {
+        self.timestamps.clear();
+    }
+}
+
+impl Default for PollInterval {
+    fn default() -> Self {
+        Self::new(MIN_POLL_INTERVAL_MS, MAX_POLL_INTERVAL_MS)
+    }
+}
+
+/// Shared heartbeat of new blocks
+#[derive(Clone, Debug)]
+pub(crate) struct BlockWatcher {
+    enabled: Arc<AtomicBool>,
+    running: Arc<AtomicBool>,
+    latest: Arc<watch::Sender<Option<BlockHeader>>>,
+}
+
+impl BlockWatcher {
+    pub fn new(enabled: bool) -> Self {
+        let (sender, _) = watch::channel(None);
+        Self {
+            enabled: Arc::new(AtomicBool::new(enabled)),
+            running: Arc::new(AtomicBool::new(false)),
+            latest: Arc::new(sender),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub fn enable(&self) {
+        self.enabled.store(true, Ordering::Relaxed);
+    }
+
+    /// Marks watcher as running. Returns `false` if it has been already running.
+    pub fn start(&self) -> bool {
+        !self.running.swap(true, Ordering::Relaxed)
+    }
+
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    pub fn latest(&self) -> Option<BlockHeader> {
+        self.latest.borrow().clone()
+    }
+
+    pub fn update(&self, header: BlockHeader) {
+        self.latest.send_replace(Some(header));
+    }
+
+    /// Forgets the latest block, i.e. when the chain has changed
+    pub fn reset(&self) {
+        self.latest.send_replace(None);
+    }
+
+    /// Stream of new blocks
+    pub fn subscribe(&self) -> impl Stream<Item = BlockHeader> {
+        let receiver = self.latest.subscribe();
+        stream::unfold(receiver, |mut receiver| async move {
+            loop {
+                receiver.changed().await.ok()?;
+                let header = receiver.borrow_and_update().clone();
+                if let Some(header) = header {
+                    return Some((header, receiver));
+                }
+            }
+        })
+    }
+}
+
+/// Fetches header of the latest block if its number differs from the given one
+pub(crate) async fn fetch_new_block<C: JsonRpcClient>(
+    client: &C,
+    last: Option<u64>,
+) -> Result<Option<BlockHeader>, C::Error> {
+    let number: U64 = client.request("eth_blockNumber", ()).await?;
+    if last == Some(number.as_u64()) {
+        return Ok(None);
+    }
+
+    let block: Option<Block<H256>> =
+        client.request("eth_getBlockByNumber", (number, false)).await?;
+    Ok(block.as_ref().map(BlockHeader::from))
+}
+
+/// Checks if the page is hidden, so polling can be paused
+fn is_page_hidden() -> bool {
+    let document = JsValue::from(gloo_utils::document());
+    js_sys::Reflect::get(&document, &"hidden".into())
+        .ok()
+        .and_then(|hidden| hidden.as_bool())
+        .unwrap_or(false)
+}
+
+/// Waits until the page becomes visible, returning immediately if it is not hidden
+pub(crate) async fn page_visible() {
+    if !is_page_hidden() {
+        return;
+    }
+
+    let (sender, receiver) = oneshot::channel();
+    let mut sender = Some(sender);
+    let _listener = EventListener::new(&gloo_utils::document(), "visibilitychange", move |_| {
+        if !is_page_hidden() {
+            if let Some(sender) = sender.take() {
+                _ = sender.send(());
+            }
+        }
+    });
+    _ = receiver.await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poll_interval_follows_average_block_gap() {
+        let mut interval = PollInterval::new(1_000, 30_000);
+        assert_eq!(interval.current(), 1_000);
+
+        interval.on_new_block(100);
+        assert_eq!(interval.current(), 1_000);
+
+        interval.on_new_block(112);
+        interval.on_new_block(124);
+        interval.on_new_block(136);
+        assert_eq!(interval.current(), 12_000);
+
+        interval.on_new_block(160);
+        assert_eq!(interval.current(), 15_000);
+    }
+
+    #[test]
+    fn test_poll_interval_stays_within_limits() {
+        let mut interval = PollInterval::new(2_000, 4_000);
+        interval.on_new_block(100);
+        interval.on_new_block(101);
+        assert_eq!(interval.current(), 2_000);
+
+        interval.on_new_block(200);
+        assert_eq!(interval.current(), 4_000);
+    }
+
+    #[test]
+    fn test_poll_interval_restarts_on_older_block() {
+        let mut interval = PollInterval::new(1_000, 30_000);
+        interval.on_new_block(1_000);
+        interval.on_new_block(1_012);
+
+        interval.on_new_block(500);
+        assert_eq!(interval.current(), 1_000);
+        interval.on_new_block(502);
+        assert_eq!(interval.current(), 2_000);
+    }
+}
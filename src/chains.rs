@@ -0,0 +1,273 @@
+use std::collections::BTreeMap;
+
+use ethers::types::{Address, H256};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Chains bundled with the library, in ethereum-lists `chains` format
+const BUNDLED_CHAINS: &str = include_str!("chains.json");
+
+/// Parts of names of test networks imported without explicit testnet flag
+const TESTNET_NAMES: &[&str] = &["testnet", "sepolia", "goerli", "holesky"];
+
+/// Native currency of a chain
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NativeCurrency {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+/// Block explorer of a chain
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BlockExplorer {
+    pub name: String,
+    pub url: String,
+}
+
+/// EVM network description
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Chain {
+    pub chain_id: u64,
+    pub name: String,
+    pub native_currency: NativeCurrency,
+    pub rpc_urls: Vec<String>,
+    pub explorers: Vec<BlockExplorer>,
+    pub testnet: bool,
+    pub eip1559: bool,
+}
+
+impl Chain {
+    /// Returns chain id in CAIP-2 format, i.e. `eip155:1`
+    pub fn caip2(&self) -> String {
+        format!("eip155:{}", self.chain_id)
+    }
+
+    /// Returns decimals of chain's native currency
+    pub fn decimals(&self) -> u8 {
+        self.native_currency.decimals
+    }
+
+    /// Returns main block explorer of the chain
+    pub fn explorer(&self) -> Option<&BlockExplorer> {
+        self.explorers.first()
+    }
+
+    /// Returns explorer link to given transaction
+    pub fn tx_url(&self, hash: &H256) -> Option<String> {
+        self.explorer_url(&format!("tx/{hash:?}"))
+    }
+
+    /// Returns explorer link to given address
+    pub fn address_url(&self, address: &Address) -> Option<String> {
+        self.explorer_url(&format!("address/{address:?}"))
+    }
+
+    /// Returns explorer link to given block
+    pub fn block_url(&self, number: u64) -> Option<String> {
+        self.explorer_url(&format!("block/{number}"))
+    }
+
+    /// Returns parameters of `wallet_addEthereumChain` request (EIP-3085)
+    pub fn add_chain_params(&self) -> Value {
+        json!({
+            "chainId": format!("0x{:x}", self.chain_id),
+            "chainName": self.name,
+            "nativeCurrency": self.native_currency,
+            "rpcUrls": self.rpc_urls,
+            "blockExplorerUrls": self.explorers.iter().map(|e| e.url.clone()).collect::<Vec<_>>(),
+        })
+    }
+
+    fn explorer_url(&self, path: &str) -> Option<String> {
+        self.explorer().map(|e| format!("{}/{path}", e.url.trim_end_matches('/')))
+    }
+}
+
+/// Registry of known chains
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChainRegistry {
+    chains: BTreeMap<u64, Chain>,
+}
+
+impl ChainRegistry {
+    /// Creates registry containing common EVM networks
+    pub fn bundled() -> Self {
+        let mut registry = Self::default();
+        registry
+            .import_ethereum_lists(BUNDLED_CHAINS)
+            .expect("Bundled chains list should be correct");
+        registry
+    }
+
+    /// Returns chain of given id
+    pub fn get(&self, chain_id: u64) -> Option<&Chain> {
+        self.chains.get(&chain_id)
+    }
+
+    /// Adds chain to the registry, replacing already known chain of the same id
+    pub fn insert(&mut self, chain: Chain) {
+        self.chains.insert(chain.chain_id, chain);
+    }
+
+    /// Iterates over all known chains ordered by chain id
+    pub fn iter(&self) -> impl Iterator<Item = &Chain> {
+        self.chains.values()
+    }
+
+    /// Imports chains described in ethereum-lists `chains` JSON format. Accepts both single chain
+    /// object and an array of chains. Returns number of imported chains.
+    pub fn import_ethereum_lists(&mut self, json: &str) -> Result<usize, serde_json::Error> {
+        let chains = match serde_json::from_str::<OneOrMany>(json)? {
+            OneOrMany::One(chain) => vec![chain],
+            OneOrMany::Many(chains) => chains,
+        };
+
+        let count = chains.len();
+        for chain in chains {
+            self.insert(chain.into());
+        }
+        Ok(count)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(EthereumListsChain),
+    Many(Vec<EthereumListsChain>),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EthereumListsChain {
+    name: String,
+    chain_id: u64,
+    #[serde(default)]
+    rpc: Vec<String>,
+    /// Not part of ethereum-lists format, set explicitly for bundled chains
+    #[serde(default)]
+    testnet: Option<bool>,
+    native_currency: NativeCurrency,
+    #[serde(default)]
+    features: Vec<EthereumListsFeature>,
+    #[serde(default)]
+    explorers: Vec<BlockExplorer>,
+}
+
+#[derive(Deserialize)]
+struct EthereumListsFeature {
+    name: String,
+}
+
+impl From<EthereumListsChain> for Chain {
+    fn from(chain: EthereumListsChain) -> Self {
+        // ethereum-lists have no testnet flag, so test networks are recognized by their names.
+        // Faucets are no hint, as some mainnets list them too.
+        let testnet = chain.testnet.unwrap_or_else(|| {
+            let name = chain.name.to_lowercase();
+            TESTNET_NAMES.iter().any(|testnet| name.contains(testnet))
+        });
+
+        Self {
+            chain_id: chain.chain_id,
+            testnet,
+            eip1559: chain.features.iter().any(|f| f.name == "EIP1559"),
+            // Urls requiring API keys are useless without them
+            rpc_urls: chain.rpc.into_iter().filter(|url| !url.contains("${")).collect(),
+            name: chain.name,
+            native_currency: chain.native_currency,
+            explorers: chain.explorers,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_bundled_registry_contains_common_chains() {
+        let registry = ChainRegistry::bundled();
+
+        let mainnet = registry.get(1).unwrap();
+        assert_eq!(mainnet.name, "Ethereum Mainnet");
+        assert_eq!(mainnet.native_currency.symbol, "ETH");
+        assert_eq!(mainnet.decimals(), 18);
+        assert!(mainnet.eip1559);
+        assert!(!mainnet.testnet);
+
+        assert!(registry.get(11155111).unwrap().testnet);
+        assert!(registry.get(421614).unwrap().testnet);
+        assert!(!registry.get(100).unwrap().testnet);
+        assert!(!registry.get(56).unwrap().eip1559);
+    }
+
+    #[test]
+    fn test_import_ethereum_lists_single_chain() {
+        let json = r#"{
+            "name": "Example Testnet",
+            "chain": "EXT",
+            "rpc": ["https://rpc.example.org", "https://mainnet.infura.io/v3/${INFURA_API_KEY}"],
+            "faucets": [],
+            "nativeCurrency": { "name": "Example", "symbol": "EXT", "decimals": 6 },
+            "infoURL": "https://example.org",
+            "shortName": "ext",
+            "chainId": 123456,
+            "networkId": 123456
+        }"#;
+
+        let mut registry = ChainRegistry::default();
+        assert_eq!(registry.import_ethereum_lists(json).unwrap(), 1);
+
+        let chain = registry.get(123456).unwrap();
+        assert_eq!(chain.rpc_urls, vec!["https://rpc.example.org".to_string()]);
+        assert_eq!(chain.decimals(), 6);
+        assert!(chain.testnet);
+        assert!(!chain.eip1559);
+        assert_eq!(chain.explorer(), None);
+        assert_eq!(chain.tx_url(&H256::zero()), None);
+    }
+
+    #[test]
+    fn test_import_recognizes_testnets_by_name() {
+        let json = r#"[
+            {
+                "name": "Gnosis",
+                "chainId": 100,
+                "faucets": ["https://gnosisfaucet.com"],
+                "nativeCurrency": { "name": "xDAI", "symbol": "XDAI", "decimals": 18 }
+            },
+            {
+                "name": "Arbitrum Sepolia",
+                "chainId": 421614,
+                "faucets": [],
+                "nativeCurrency": { "name": "Sepolia Ether", "symbol": "ETH", "decimals": 18 }
+            }
+        ]"#;
+
+        let mut registry = ChainRegistry::default();
+        assert_eq!(registry.import_ethereum_lists(json).unwrap(), 2);
+
+        assert!(!registry.get(100).unwrap().testnet);
+        assert!(registry.get(421614).unwrap().testnet);
+    }
+
+    #[test]
+    fn test_explorer_urls() {
+        let registry = ChainRegistry::bundled();
+        let mainnet = registry.get(1).unwrap();
+        let address = Address::from_str("0x00000000219ab540356cBB839Cbe05303d7705Fa").unwrap();
+
+        assert_eq!(
+            mainnet.address_url(&address).unwrap(),
+            "https://etherscan.io/address/0x00000000219ab540356cbb839cbe05303d7705fa"
+        );
+        assert_eq!(mainnet.block_url(1).unwrap(), "https://etherscan.io/block/1");
+        assert_eq!(
+            mainnet.tx_url(&H256::zero()).unwrap(),
+            format!("https://etherscan.io/tx/0x{}", "0".repeat(64))
+        );
+    }
+}
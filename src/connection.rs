@@ -0,0 +1,130 @@
+use std::sync::{Arc, RwLock};
+
+/// State of the connection to the wallet
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// No wallet is connected
+    #[default]
+    Idle,
+    Connecting,
+    /// WalletConnect pairing uri waits to be approved by the wallet
+    AwaitingPairing,
+    Connected,
+    /// Wallet is switching to another chain
+    Switching,
+    /// Broken connection is being reestablished
+    Reconnecting,
+    Disconnecting,
+    /// Connecting failed with given reason
+    Failed(String),
+}
+
+impl ConnectionState {
+    /// Checks if wallet can be used, possibly after pending switch or reconnection finishes
+    pub fn is_connected(&self) -> bool {
+        matches!(self, Self::Connected | Self::Switching | Self::Reconnecting)
+    }
+
+    /// Checks if the state can move to given one. Moving to the same state is not a transition.
+    pub fn can_transition(&self, to: &ConnectionState) -> bool {
+        use ConnectionState::*;
+
+        self != to
+            && match (self, to) {
+                (Idle | Failed(_) | Disconnecting, Connecting) => true,
+                (Connecting | AwaitingPairing, AwaitingPairing | Connected | Failed(_)) => true,
+                // Wallet reconnected on its own, i.e. injected wallet was unlocked again
+                (Idle, Connected) => true,
+                (Connected, Switching | Reconnecting) => true,
+                (Switching, Connected | Reconnecting) => true,
+                (Reconnecting, Connected) => true,
+                (
+                    Connecting | AwaitingPairing | Connected | Switching | Reconnecting,
+                    Disconnecting,
+                ) => true,
+                // Wallet can drop the connection at any time
+                (_, Idle) => true,
+                _ => false,
+            }
+    }
+}
+
+/// Result of attempted transition
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Transition {
+    Changed(ConnectionState),
+    Unchanged,
+    Illegal,
+}
+
+/// Connection state shared by all clones of `Ethereum`
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ConnectionMachine {
+    state: Arc<RwLock<ConnectionState>>,
+}
+
+impl ConnectionMachine {
+    pub fn state(&self) -> ConnectionState {
+        self.state.read().map(|s| s.clone()).unwrap_or_default()
+    }
+
+    /// Moves to given state if the transition is legal
+    pub fn transition(&self, to: ConnectionState) -> Transition {
+        let Ok(mut state) = self.state.write() else {
+            return Transition::Illegal;
+        };
+
+        if *state == to {
+            Transition::Unchanged
+        } else if state.can_transition(&to) {
+            *state = to.clone();
+            Transition::Changed(to)
+        } else {
+            Transition::Illegal
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_walletconnect_lifecycle() {
+        let machine = ConnectionMachine::default();
+
+        for state in [
+            ConnectionState::Connecting,
+            ConnectionState::AwaitingPairing,
+            ConnectionState::Connected,
+            ConnectionState::Switching,
+            ConnectionState::Connected,
+            ConnectionState::Reconnecting,
+            ConnectionState::Connected,
+            ConnectionState::Disconnecting,
+            ConnectionState::Idle,
+        ] {
+            assert_eq!(machine.transition(state.clone()), Transition::Changed(state));
+        }
+    }
+
+    #[test]
+    fn test_illegal_and_repeated_transitions() {
+        let machine = ConnectionMachine::default();
+
+        assert_eq!(machine.transition(ConnectionState::Idle), Transition::Unchanged);
+        assert_eq!(machine.transition(ConnectionState::Reconnecting), Transition::Illegal);
+        assert_eq!(machine.transition(ConnectionState::Disconnecting), Transition::Illegal);
+
+        machine.transition(ConnectionState::Connecting);
+        let failed = ConnectionState::Failed("rejected".to_string());
+        assert_eq!(machine.transition(failed.clone()), Transition::Changed(failed));
+        assert_eq!(machine.transition(ConnectionState::Connected), Transition::Illegal);
+        assert_eq!(machine.state(), ConnectionState::Failed("rejected".to_string()));
+
+        machine.transition(ConnectionState::Connecting);
+        machine.transition(ConnectionState::Connected);
+        assert_eq!(machine.transition(ConnectionState::Connected), Transition::Unchanged);
+        assert!(machine.state().is_connected());
+    }
+}
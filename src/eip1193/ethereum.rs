@@ -29,6 +29,17 @@ impl Ethereum {
             Err(Eip1193Error::JsNoEthereum)
         }
     }
+
+    /// Provider announced with given rdns, or the default one
+    pub(crate) fn get(rdns: Option<&str>) -> Result<Self, Eip1193Error> {
+        match rdns {
+            None => Self::default_opt(),
+            Some(rdns) => match get_announced_provider_js(rdns) {
+                Ok(Some(eth)) => Ok(eth),
+                _ => Err(Eip1193Error::JsNoEthereum),
+            },
+        }
+    }
 }
 
 #[wasm_bindgen(inline_js = "export function get_provider_js() {return window.ethereum}")]
@@ -36,3 +47,42 @@ extern "C" {
     #[wasm_bindgen(catch)]
     fn get_provider_js() -> Result<Option<Ethereum>, JsValue>;
 }
+
+// Wallets answer EIP-6963 request synchronously, so announced providers are known right after
+// it is dispatched
+#[wasm_bindgen(inline_js = r#"
+const providers = new Map();
+let listening = false;
+export function request_providers_js() {
+    if (!listening) {
+        window.addEventListener("eip6963:announceProvider", (event) => {
+            providers.set(event.detail.info.rdns, event.detail);
+        });
+        listening = true;
+    }
+    window.dispatchEvent(new Event("eip6963:requestProvider"));
+    return Array.from(providers.values()).map((detail) => detail.info);
+}
+export function get_announced_provider_js(rdns) {
+    return providers.get(rdns)?.provider;
+}
+export function get_provider_flags_js() {
+    const flags = [];
+    for (const key in window.ethereum ?? {}) {
+        if (key.startsWith("is") && window.ethereum[key] === true) {
+            flags.push(key);
+        }
+    }
+    return flags;
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(catch)]
+    pub(crate) fn request_providers_js() -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(catch)]
+    fn get_announced_provider_js(rdns: &str) -> Result<Option<Ethereum>, JsValue>;
+
+    #[wasm_bindgen(catch)]
+    pub(crate) fn get_provider_flags_js() -> Result<JsValue, JsValue>;
+}
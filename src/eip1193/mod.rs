@@ -1,9 +1,12 @@
 pub mod error;
 mod ethereum;
 
-use self::{error::Eip1193Error, ethereum::Ethereum};
+use self::{
+    error::Eip1193Error,
+    ethereum::{get_provider_flags_js, request_providers_js, Ethereum},
+};
 
-use crate::event::WalletEvent;
+use crate::{event::WalletEvent, wallets::ProviderInfo};
 use async_trait::async_trait;
 use ethers::{
     providers::JsonRpcClient,
@@ -13,15 +16,57 @@ use ethers::{
 use futures::channel::oneshot;
 use gloo_utils::format::JsValueSerdeExt;
 use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    fmt::{Debug, Formatter, Result as FmtResult},
+    sync::{Arc, RwLock},
+};
+use unsafe_send_sync::UnsafeSendSync;
 use wasm_bindgen::{closure::Closure, JsValue};
 use wasm_bindgen_futures::spawn_local;
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 // All attributes this library needs is thread unsafe.
 // But wasm itself is a single threaded... something.
 // To avoid problems with Send and Sync, all these parameters are
 // fetched whenever it is needed
-pub(crate) struct Eip1193 {}
+pub(crate) struct Eip1193 {
+    /// EIP-6963 rdns of selected provider, `window.ethereum` is used if unset
+    rdns: Option<String>,
+    /// Listeners are shared by all clones, so rebinding moves them everywhere
+    listeners: Arc<RwLock<UnsafeSendSync<Listeners>>>,
+}
+
+impl Debug for Eip1193 {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "EIP-1193 provider {:?}", self.rdns)
+    }
+}
+
+type Callback = Closure<dyn FnMut(JsValue)>;
+
+/// Listeners of provider events together with provider object they are registered on. They are
+/// removed from it once the last clone of the provider is dropped.
+#[derive(Default)]
+struct Listeners {
+    target: Option<Ethereum>,
+    callbacks: Vec<(&'static str, Callback)>,
+}
+
+impl Listeners {
+    fn unbind(&self) {
+        if let Some(target) = &self.target {
+            for (event, callback) in &self.callbacks {
+                target.removeListener(event, callback);
+            }
+        }
+    }
+}
+
+impl Drop for Listeners {
+    fn drop(&mut self) {
+        self.unbind();
+    }
+}
 
 #[cfg_attr(target_arch = "wasm32", async_trait(? Send))]
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
@@ -40,8 +85,9 @@ impl JsonRpcClient for Eip1193 {
         let m = method.to_string();
 
         let parsed_params = parse_params(params, &m).unwrap_or_default();
+        let rdns = self.rdns.clone();
         spawn_local(async move {
-            if let Ok(ethereum) = Ethereum::default_opt() {
+            if let Ok(ethereum) = Ethereum::get(rdns.as_deref()) {
                 // We're using bare-metal JsObject creation.
                 // wasm_bindgen struggles to build error-free struct bridges
                 // so often rather than not the message receiver is unable to parse the call.
@@ -98,19 +144,67 @@ impl Eip1193 {
         Ethereum::default_opt().is_ok()
     }
 
+    /// Asks wallets to announce themselves with EIP-6963, returns all announced so far
+    pub fn announced() -> Vec<ProviderInfo> {
+        request_providers_js().ok().and_then(|infos| infos.into_serde().ok()).unwrap_or_default()
+    }
+
+    /// Flags like `isMetaMask` set on `window.ethereum`
+    pub fn provider_flags() -> Vec<String> {
+        get_provider_flags_js().ok().and_then(|flags| flags.into_serde().ok()).unwrap_or_default()
+    }
+
     pub fn new() -> Self {
-        Eip1193 {}
+        Eip1193 { rdns: None, listeners: Default::default() }
+    }
+
+    /// Provider announced with given rdns
+    pub fn with_rdns(rdns: &str) -> Self {
+        Eip1193 { rdns: Some(rdns.to_string()), listeners: Default::default() }
+    }
+
+    /// Checks if selected provider is available
+    pub fn is_present(&self) -> bool {
+        Ethereum::get(self.rdns.as_deref()).is_ok()
+    }
+
+    pub fn rdns(&self) -> Option<&str> {
+        self.rdns.as_deref()
     }
 
     pub fn on(
-        self,
+        &self,
         event: WalletEvent,
         callback: Box<dyn FnMut(JsValue)>,
     ) -> Result<(), Eip1193Error> {
-        let ethereum = Ethereum::default_opt()?;
+        let ethereum = Ethereum::get(self.rdns.as_deref())?;
         let closure = Closure::wrap(callback);
         ethereum.on(event.as_str(), &closure);
-        closure.forget();
+
+        let mut listeners = self.listeners.write().unwrap();
+        listeners.target = Some(ethereum);
+        listeners.callbacks.push((event.as_str(), closure));
+        Ok(())
+    }
+
+    /// Moves listeners to provider object injected again, i.e. after the extension was reloaded,
+    /// as the old object no longer emits any events
+    pub fn rebind(&self) -> Result<(), Eip1193Error> {
+        if self.rdns.is_some() {
+            // Reloaded extension announces its new provider only when asked
+            Self::announced();
+        }
+        let ethereum = Ethereum::get(self.rdns.as_deref())?;
+
+        let mut listeners = self.listeners.write().unwrap();
+        if listeners.target.as_ref().is_some_and(|target| JsValue::eq(target, &*ethereum)) {
+            return Ok(());
+        }
+        listeners.unbind();
+        for (event, callback) in &listeners.callbacks {
+            ethereum.on(event, callback);
+        }
+        listeners.target = Some(ethereum);
         Ok(())
     }
 }
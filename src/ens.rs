@@ -0,0 +1,219 @@
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+use ethers::{
+    providers::{Http, Middleware, Provider, ProviderError},
+    types::Address,
+};
+use url::Url;
+
+const DEFAULT_IPFS_GATEWAY: &str = "https://ipfs.io/";
+
+/// ENS records of an address
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnsProfile {
+    pub address: Address,
+    pub name: Option<String>,
+    pub avatar: Option<Url>,
+}
+
+/// ENS resolver working against mainnet RPC node regardless of currently connected chain
+#[derive(Clone, Debug)]
+pub struct EnsResolver {
+    provider: Provider<Http>,
+    gateway: Url,
+    cache: Arc<Mutex<HashMap<Address, EnsProfile>>>,
+}
+
+impl EnsResolver {
+    /// Creates resolver for given mainnet RPC node and optional IPFS gateway
+    pub fn new(rpc_node: &str, gateway: Option<&str>) -> Result<Self, url::ParseError> {
+        let provider = Provider::new(Http::from_str(rpc_node)?);
+        let mut gateway = Url::parse(gateway.unwrap_or(DEFAULT_IPFS_GATEWAY))?;
+        if !gateway.path().ends_with('/') {
+            gateway.set_path(&format!("{}/", gateway.path()));
+        }
+        Ok(Self { provider, gateway, cache: Arc::new(Mutex::new(HashMap::new())) })
+    }
+
+    /// Resolves ENS name to an address
+    pub async fn resolve_name(&self, name: &str) -> Result<Option<Address>, ProviderError> {
+        match normalize(name) {
+            Some(name) => not_found_as_none(self.provider.resolve_name(&name).await),
+            None => Ok(None),
+        }
+    }
+
+    /// Looks up primary name of given address. Name is returned only if it resolves back to the
+    /// same address.
+    pub async fn lookup_address(&self, address: Address) -> Result<Option<String>, ProviderError> {
+        let name = not_found_as_none(self.provider.lookup_address(address).await)?;
+        Ok(name.and_then(|n| normalize(&n)))
+    }
+
+    /// Resolves avatar record of ENS name into an url that can be used directly in the browser
+    pub async fn resolve_avatar(&self, name: &str) -> Result<Option<Url>, ProviderError> {
+        let Some(name) = normalize(name) else {
+            return Ok(None);
+        };
+        let record = match not_found_as_none(self.provider.resolve_field(&name, "avatar").await)? {
+            Some(record) if !record.is_empty() => record,
+            _ => return Ok(None),
+        };
+
+        let url = match Url::parse(&record) {
+            Ok(url) => url,
+            Err(_) => return Ok(None),
+        };
+
+        match url.scheme() {
+            "http" | "https" | "data" => Ok(Some(url)),
+            "ipfs" | "ipns" => Ok(self.gateway_link(&url)),
+            // NFT avatars need ownership verification and metadata fetching
+            "eip155" => {
+                let image = not_found_as_none(self.provider.resolve_avatar(&name).await)?;
+                Ok(image.and_then(|i| match (i.scheme(), i.host_str()) {
+                    ("ipfs" | "ipns", _) | (_, Some("ipfs.io")) => self.gateway_link(&i),
+                    _ => Some(i),
+                }))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Fetches name and avatar of an address, using cached profile if available
+    pub async fn profile(&self, address: Address) -> Result<EnsProfile, ProviderError> {
+        if let Some(profile) = self.cached(&address) {
+            return Ok(profile);
+        }
+
+        let name = self.lookup_address(address).await?;
+        let avatar = match &name {
+            Some(name) => self.resolve_avatar(name).await?,
+            None => None,
+        };
+
+        let profile = EnsProfile { address, name, avatar };
+        self.remember(profile.clone());
+        Ok(profile)
+    }
+
+    fn remember(&self, profile: EnsProfile) {
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.insert(profile.address, profile);
+        }
+    }
+
+    /// Returns cached profile of an address
+    pub fn cached(&self, address: &Address) -> Option<EnsProfile> {
+        self.cache.lock().ok().and_then(|c| c.get(address).cloned())
+    }
+
+    /// Removes all cached profiles
+    pub fn clear_cache(&self) {
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.clear();
+        }
+    }
+
+    /// Translates `ipfs://` and `ipns://` links (also ones served by public ipfs.io gateway) into
+    /// configured gateway link
+    fn gateway_link(&self, url: &Url) -> Option<Url> {
+        let path = match url.scheme() {
+            "ipfs" | "ipns" => {
+                let path = url.as_str().split_once("://").map(|(_, p)| p)?;
+                let path = path.trim_start_matches("ipfs/").trim_start_matches("ipns/");
+                format!("{}/{path}", url.scheme())
+            }
+            _ => url.path().trim_start_matches('/').to_string(),
+        };
+        self.gateway.join(&path).ok()
+    }
+}
+
+/// Normalises name the way it is hashed by ENS. Returns `None` for names which cannot be
+/// registered, i.e. empty ones.
+fn normalize(name: &str) -> Option<String> {
+    let name = name.trim().trim_end_matches('.').to_lowercase();
+    let valid = name.contains('.')
+        && name.split('.').all(|label| !label.is_empty() && !label.contains(char::is_whitespace));
+    valid.then_some(name)
+}
+
+/// ENS lookups for names without records are not errors from the user perspective
+fn not_found_as_none<T>(result: Result<T, ProviderError>) -> Result<Option<T>, ProviderError> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(ProviderError::EnsError(_) | ProviderError::EnsNotOwned(_)) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    /// Resolver of a node which cannot be reached, answering only from cache or without lookups
+    fn offline_resolver() -> EnsResolver {
+        EnsResolver::new("http://127.0.0.1:9", Some("https://gateway.example.org/x")).unwrap()
+    }
+
+    #[test]
+    fn test_names_are_normalized() {
+        assert_eq!(normalize(" Vitalik.ETH. "), Some("vitalik.eth".to_string()));
+        assert_eq!(normalize("pay.Vitalik.eth"), Some("pay.vitalik.eth".to_string()));
+        assert_eq!(normalize(""), None);
+        assert_eq!(normalize("eth"), None);
+        assert_eq!(normalize("vitalik..eth"), None);
+        assert_eq!(normalize("vita lik.eth"), None);
+    }
+
+    #[test]
+    fn test_missing_names_resolve_to_nothing() {
+        let resolver = offline_resolver();
+        assert_eq!(block_on(resolver.resolve_name("")).unwrap(), None);
+        assert_eq!(block_on(resolver.resolve_avatar("  ")).unwrap(), None);
+    }
+
+    #[test]
+    fn test_profiles_are_cached_per_address() {
+        let resolver = offline_resolver();
+        let named = EnsProfile {
+            address: Address::repeat_byte(1),
+            name: Some("alice.eth".to_string()),
+            avatar: None,
+        };
+        let unnamed = EnsProfile { address: Address::repeat_byte(2), name: None, avatar: None };
+        resolver.remember(named.clone());
+        resolver.remember(unnamed.clone());
+
+        assert_eq!(block_on(resolver.profile(named.address)).unwrap(), named);
+        assert_eq!(block_on(resolver.profile(unnamed.address)).unwrap(), unnamed);
+        assert_eq!(resolver.cached(&Address::repeat_byte(3)), None);
+
+        resolver.clear_cache();
+        assert_eq!(resolver.cached(&named.address), None);
+    }
+
+    #[test]
+    fn test_ipfs_avatars_use_configured_gateway() {
+        let resolver = offline_resolver();
+        let link = |url: &str| resolver.gateway_link(&Url::parse(url).unwrap()).unwrap();
+        assert_eq!(
+            link("ipfs://QmHash/avatar.png").as_str(),
+            "https://gateway.example.org/x/ipfs/QmHash/avatar.png"
+        );
+        assert_eq!(
+            link("ipfs://ipfs/QmHash").as_str(),
+            "https://gateway.example.org/x/ipfs/QmHash"
+        );
+        assert_eq!(
+            link("https://ipfs.io/ipfs/QmHash").as_str(),
+            "https://gateway.example.org/x/ipfs/QmHash"
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+use super::ExplorerResponse;
+
+const EXPLORER_CACHE_KEY: &str = "ETHERS_WEB_EXPLORER_CACHE";
+/// Time explorer responses are served from cache without revalidation
+pub const DEFAULT_CACHE_TTL_SECS: u64 = 60 * 60;
+/// Number of distinct queries kept in cache
+pub const MAX_CACHED_QUERIES: usize = 20;
+
+/// Explorer response stored together with its validator
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub url: String,
+    pub etag: Option<String>,
+    /// Unix timestamp of last successful fetch or revalidation
+    pub fetched_at: u64,
+    pub response: ExplorerResponse,
+}
+
+impl CachedResponse {
+    pub fn is_fresh(&self, now: u64, ttl: u64) -> bool {
+        now < self.fetched_at.saturating_add(ttl)
+    }
+}
+
+/// Explorer responses kept in local storage, keyed by request url
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ExplorerCache {
+    pub entries: Vec<CachedResponse>,
+}
+
+impl ExplorerCache {
+    pub fn load() -> Self {
+        LocalStorage::get(EXPLORER_CACHE_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        if let Err(err) = LocalStorage::set(EXPLORER_CACHE_KEY, self) {
+            log::error!("Explorer cache not saved {err:?}");
+        }
+    }
+
+    pub fn clear() {
+        LocalStorage::delete(EXPLORER_CACHE_KEY);
+    }
+
+    pub fn get(&self, url: &str) -> Option<&CachedResponse> {
+        self.entries.iter().find(|e| e.url == url)
+    }
+
+    /// Stores response, evicting least recently fetched ones above the limit
+    pub fn insert(&mut self, entry: CachedResponse) {
+        self.entries.retain(|e| e.url != entry.url);
+        self.entries.push(entry);
+        if self.entries.len() > MAX_CACHED_QUERIES {
+            self.entries.sort_by_key(|e| std::cmp::Reverse(e.fetched_at));
+            self.entries.truncate(MAX_CACHED_QUERIES);
+        }
+    }
+
+    /// Marks cached response as still valid after explorer answered `304 Not Modified`
+    pub fn touch(&mut self, url: &str, now: u64) -> Option<&CachedResponse> {
+        let entry = self.entries.iter_mut().find(|e| e.url == url)?;
+        entry.fetched_at = now;
+        Some(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(url: &str, fetched_at: u64) -> CachedResponse {
+        CachedResponse {
+            url: url.to_string(),
+            etag: Some("\"v1\"".to_string()),
+            fetched_at,
+            response: ExplorerResponse { listings: Default::default(), count: 0, total: 0 },
+        }
+    }
+
+    #[test]
+    fn test_cache_evicts_oldest_entries() {
+        let mut cache = ExplorerCache::default();
+        for i in 0..MAX_CACHED_QUERIES as u64 + 2 {
+            cache.insert(entry(&format!("url{i}"), 100 + i));
+        }
+        cache.insert(entry("url5", 1_000));
+
+        assert_eq!(cache.entries.len(), MAX_CACHED_QUERIES);
+        assert!(cache.get("url0").is_none() && cache.get("url1").is_none());
+        assert_eq!(cache.get("url5").unwrap().fetched_at, 1_000);
+    }
+
+    #[test]
+    fn test_revalidated_entry_is_fresh_again() {
+        let mut cache = ExplorerCache::default();
+        cache.insert(entry("url", 100));
+
+        assert!(!cache
+            .get("url")
+            .unwrap()
+            .is_fresh(100 + DEFAULT_CACHE_TTL_SECS, DEFAULT_CACHE_TTL_SECS));
+        let entry = cache.touch("url", 5_000).unwrap();
+        assert!(entry.is_fresh(5_001, DEFAULT_CACHE_TTL_SECS));
+    }
+}
@@ -0,0 +1,442 @@
+use std::cmp::Ordering;
+
+use log::error;
+use reqwest::{
+    header::{ETAG, IF_NONE_MATCH},
+    StatusCode,
+};
+use url::Url;
+
+use super::{
+    cache::{CachedResponse, ExplorerCache, DEFAULT_CACHE_TTL_SECS},
+    ExplorerResponse, ImageSize, WalletDescription,
+};
+
+/// WalletConnect explorer used when none was configured
+pub const DEFAULT_EXPLORER_URL: &str = "https://explorer-api.walletconnect.com/";
+/// Number of wallets fetched per page by default
+pub const DEFAULT_PAGE_SIZE: u32 = 100;
+
+/// Wallet listing query
+#[derive(Clone, Debug, PartialEq)]
+pub struct WalletQuery {
+    /// Only wallets supporting all of these chains are listed
+    pub chains: Vec<u64>,
+    pub search: Option<String>,
+    /// Page number, starting from 1
+    pub page: u32,
+    pub entries: u32,
+    /// Ids of wallets to be listed exclusively
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    /// Ids of wallets listed on top of the first page, in given order. Other pages skip them.
+    pub featured: Vec<String>,
+}
+
+impl WalletQuery {
+    /// Query of the listing without featured wallets
+    fn listing(&self) -> Self {
+        let mut listing = self.clone();
+        let featured = self.featured.iter().filter(|id| !self.exclude.contains(id));
+        listing.exclude.extend(featured.cloned());
+        listing.featured.clear();
+        listing
+    }
+
+    /// Query of featured wallets, fetched by their ids for the first page only
+    fn featured(&self) -> Option<Self> {
+        let ids = self
+            .featured
+            .iter()
+            .filter(|id| self.include.is_empty() || self.include.contains(id))
+            .filter(|id| !self.exclude.contains(id))
+            .cloned()
+            .collect::<Vec<_>>();
+        if self.page > 1 || ids.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            page: 1,
+            entries: ids.len() as u32,
+            include: ids,
+            featured: self.featured.clone(),
+            ..self.clone()
+        })
+    }
+}
+
+impl Default for WalletQuery {
+    fn default() -> Self {
+        Self {
+            chains: Vec::new(),
+            search: None,
+            page: 1,
+            entries: DEFAULT_PAGE_SIZE,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            featured: Vec::new(),
+        }
+    }
+}
+
+/// Origin of listed wallets
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WalletSource {
+    #[default]
+    Explorer,
+    /// Cached explorer response, possibly stale if explorer was unreachable
+    Cache,
+    /// Wallets bundled with the library, listed when explorer was unreachable
+    Bundled,
+}
+
+/// Single page of ranked wallets
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WalletPage {
+    pub wallets: Vec<WalletDescription>,
+    pub source: WalletSource,
+    pub page: u32,
+    /// Number of wallets returned by the explorer for this page
+    pub count: u32,
+    /// Number of wallets matching the query, besides featured ones
+    pub total: u32,
+}
+
+impl WalletPage {
+    /// Checks if there are more pages to fetch
+    pub fn has_more(&self, entries: u32) -> bool {
+        self.page.saturating_mul(entries) < self.total
+    }
+}
+
+/// WalletConnect explorer API client
+#[derive(Clone, Debug)]
+pub struct ExplorerClient {
+    base_url: Url,
+    project_id: String,
+    /// Time responses are served from cache without revalidation, no caching if unset
+    cache_ttl: Option<u64>,
+    client: reqwest::Client,
+}
+
+impl ExplorerClient {
+    pub fn new(project_id: &str) -> Self {
+        Self::with_base_url(project_id, Url::parse(DEFAULT_EXPLORER_URL).unwrap())
+    }
+
+    /// Client of self-hosted or proxied explorer
+    pub fn with_base_url(project_id: &str, mut base_url: Url) -> Self {
+        // Joining paths to base without trailing slash would replace its last segment
+        if !base_url.path().ends_with('/') {
+            base_url.set_path(&format!("{}/", base_url.path()));
+        }
+        Self {
+            base_url,
+            project_id: project_id.to_string(),
+            cache_ttl: Some(DEFAULT_CACHE_TTL_SECS),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Sets time responses are served from cache without revalidation. Caching is disabled
+    /// when unset.
+    pub fn with_cache_ttl(mut self, cache_ttl: Option<u64>) -> Self {
+        self.cache_ttl = cache_ttl;
+        self
+    }
+
+    pub fn base_url(&self) -> &Url {
+        &self.base_url
+    }
+
+    /// Fetches page of wallets matching the query. When explorer is unreachable, bundled
+    /// wallets are listed instead.
+    pub async fn fetch_wallets(&self, query: &WalletQuery) -> WalletPage {
+        match self.fetch_remote(query).await {
+            Ok(page) => page,
+            Err(err) => {
+                error!("Explorer unreachable, listing bundled wallets {err:?}");
+                self.offline_page(query)
+            }
+        }
+    }
+
+    /// Fetches page of wallets from explorer, with featured wallets on top of the first page
+    pub async fn fetch_remote(&self, query: &WalletQuery) -> Result<WalletPage, reqwest::Error> {
+        let mut page = self.fetch_listing(&query.listing()).await?;
+        if let Some(featured) = query.featured() {
+            let mut featured = self.fetch_listing(&featured).await?;
+            rank(&mut featured.wallets, &query.featured);
+            page.wallets.splice(0..0, featured.wallets);
+        }
+        Ok(page)
+    }
+
+    /// Fetches page of wallets from explorer. Cached responses are used while fresh, then
+    /// revalidated with their ETag, and used regardless of age if explorer is unreachable.
+    async fn fetch_listing(&self, query: &WalletQuery) -> Result<WalletPage, reqwest::Error> {
+        let url = self.wallets_url(query);
+        let Some(ttl) = self.cache_ttl else {
+            let response = self.client.get(url).send().await?.error_for_status()?.json().await?;
+            return Ok(self.to_page(response, query, WalletSource::Explorer));
+        };
+
+        let now = chrono::Utc::now().timestamp() as u64;
+        let mut cache = ExplorerCache::load();
+        let cached = cache.get(url.as_str()).cloned();
+        if let Some(cached) = cached.as_ref().filter(|c| c.is_fresh(now, ttl)) {
+            return Ok(self.to_page(cached.response.clone(), query, WalletSource::Cache));
+        }
+
+        let mut request = self.client.get(url.clone());
+        if let Some(etag) = cached.as_ref().and_then(|c| c.etag.as_deref()) {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        let response = match request.send().await.and_then(|r| r.error_for_status()) {
+            Ok(response) => response,
+            Err(err) => {
+                return match cached {
+                    Some(cached) => Ok(self.to_page(cached.response, query, WalletSource::Cache)),
+                    None => Err(err),
+                }
+            }
+        };
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(cached) = cache.touch(url.as_str(), now).cloned() {
+                cache.save();
+                return Ok(self.to_page(cached.response, query, WalletSource::Cache));
+            }
+        }
+
+        // Explorer has to expose the header with CORS for the browser to pass it through
+        let etag = response.headers().get(ETAG).and_then(|e| e.to_str().ok()).map(String::from);
+        let response: ExplorerResponse = response.json().await?;
+        cache.insert(CachedResponse {
+            url: url.to_string(),
+            etag,
+            fetched_at: now,
+            response: response.clone(),
+        });
+        cache.save();
+        Ok(self.to_page(response, query, WalletSource::Explorer))
+    }
+
+    /// Page of bundled wallets matching the query
+    pub fn offline_page(&self, query: &WalletQuery) -> WalletPage {
+        let mut page = self.to_page(ExplorerResponse::bundled(), query, WalletSource::Bundled);
+        rank(&mut page.wallets, &query.featured);
+        if let Some(search) = query.search.as_deref().map(|s| s.trim().to_lowercase()) {
+            page.wallets.retain(|w| {
+                w.name.to_lowercase().contains(&search)
+                    || w.short_name.to_lowercase().contains(&search)
+            });
+        }
+
+        page.total = page.wallets.len() as u32;
+        let skip = (page.page - 1).saturating_mul(query.entries) as usize;
+        page.wallets = page.wallets.into_iter().skip(skip).take(query.entries as usize).collect();
+        page.count = page.wallets.len() as u32;
+        page
+    }
+
+    /// Url of explorer's wallets listing for given query
+    pub fn wallets_url(&self, query: &WalletQuery) -> Url {
+        let mut url = self.base_url.join("v3/wallets").unwrap();
+        {
+            let mut params = url.query_pairs_mut();
+            params
+                .append_pair("projectId", &self.project_id)
+                .append_pair("page", &query.page.max(1).to_string())
+                .append_pair("entries", &query.entries.to_string());
+            if let Some(search) = query.search.as_deref().filter(|s| !s.trim().is_empty()) {
+                params.append_pair("search", search.trim());
+            }
+            if !query.chains.is_empty() {
+                params.append_pair(
+                    "chains",
+                    &join(query.chains.iter().map(|c| format!("eip155:{c}"))),
+                );
+            }
+            if !query.include.is_empty() {
+                params.append_pair("ids", &query.include.join(","));
+            }
+            if !query.exclude.is_empty() {
+                params.append_pair("excludedIds", &query.exclude.join(","));
+            }
+        }
+        url
+    }
+
+    /// Url of wallet's logo
+    pub fn logo_url(&self, wallet: &WalletDescription, size: ImageSize) -> Url {
+        let mut url =
+            self.base_url.join(&format!("v3/logo/{}/{}", size.mark(), wallet.image_id)).unwrap();
+        url.query_pairs_mut().append_pair("projectId", &self.project_id);
+        url
+    }
+
+    /// Converts explorer response into page, keeping explorer's order. Filters are applied
+    /// again, as explorer ignores ones it does not understand.
+    pub(crate) fn to_page(
+        &self,
+        response: ExplorerResponse,
+        query: &WalletQuery,
+        source: WalletSource,
+    ) -> WalletPage {
+        let wallets = response
+            .parse_wallets(&self.project_id)
+            .into_iter()
+            .filter(|w| query.include.is_empty() || query.include.contains(&w.id))
+            .filter(|w| !query.exclude.contains(&w.id))
+            .filter(|w| query.chains.iter().all(|c| w.chains.contains(c)))
+            .collect::<Vec<_>>();
+
+        WalletPage {
+            wallets,
+            source,
+            page: query.page.max(1),
+            count: response.count,
+            total: response.total,
+        }
+    }
+}
+
+/// Orders wallets deterministically: featured ones first in given order, then by name and id
+pub fn rank(wallets: &mut [WalletDescription], featured: &[String]) {
+    let position = |w: &WalletDescription| featured.iter().position(|id| id == &w.id);
+    wallets.sort_by(|a, b| match (position(a), position(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => {
+            a.name.to_lowercase().cmp(&b.name.to_lowercase()).then_with(|| a.id.cmp(&b.id))
+        }
+    });
+}
+
+fn join(values: impl Iterator<Item = String>) -> String {
+    values.collect::<Vec<_>>().join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wallet(id: &str, name: &str, chains: Vec<u64>) -> WalletDescription {
+        WalletDescription {
+            id: id.to_string(),
+            short_name: name.to_string(),
+            name: name.to_string(),
+            chains,
+            image_id: format!("{id}-logo"),
+            project_id: "project".to_string(),
+            desktop_schema: None,
+            desktop_universal: None,
+            mobile_schema: Some(format!("{id}://")),
+            mobile_universal: None,
+            rdns: None,
+            injected_flags: vec![],
+            app: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_wallets_url() {
+        let client = ExplorerClient::with_base_url(
+            "project",
+            Url::parse("https://proxy.example.org/explorer").unwrap(),
+        );
+        let query = WalletQuery {
+            chains: vec![1, 137],
+            search: Some(" rain ".to_string()),
+            page: 2,
+            entries: 20,
+            exclude: vec!["a".to_string(), "b".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            client.wallets_url(&query).as_str(),
+            "https://proxy.example.org/explorer/v3/wallets?projectId=project&page=2&entries=20\
+             &search=rain&chains=eip155%3A1%2Ceip155%3A137&excludedIds=a%2Cb"
+        );
+    }
+
+    #[test]
+    fn test_featured_wallets_are_queried_by_ids() {
+        let client = ExplorerClient::new("project");
+        let query = WalletQuery {
+            entries: 20,
+            exclude: vec!["x".to_string()],
+            featured: vec!["b".to_string(), "x".to_string(), "a".to_string()],
+            ..Default::default()
+        };
+
+        let featured = query.featured().unwrap();
+        assert_eq!(featured.include, ["b", "a"]);
+        assert!(client.wallets_url(&featured).as_str().contains("&entries=2&ids=b%2Ca&"));
+
+        let listing = query.listing();
+        assert_eq!(listing.exclude, ["x", "b", "a"]);
+        assert!(listing.featured.is_empty());
+
+        assert_eq!(WalletQuery { page: 2, ..query }.featured(), None);
+    }
+
+    #[test]
+    fn test_rank_puts_featured_first() {
+        let mut wallets = vec![
+            wallet("3", "zerion", vec![1]),
+            wallet("1", "Argent", vec![1]),
+            wallet("2", "Rainbow", vec![1]),
+            wallet("4", "MetaMask", vec![1]),
+        ];
+        rank(&mut wallets, &["4".to_string(), "2".to_string()]);
+
+        let ids = wallets.iter().map(|w| w.id.as_str()).collect::<Vec<_>>();
+        assert_eq!(ids, ["4", "2", "1", "3"]);
+    }
+
+    #[test]
+    fn test_page_filters_wallets() {
+        let client = ExplorerClient::new("project");
+        let response: ExplorerResponse = serde_json::from_value(serde_json::json!({
+            "listings": {
+                "1": { "id": "1", "name": "One", "chains": ["eip155:1"], "image_id": "i",
+                       "mobile": { "native": "one://", "universal": null }, "desktop": null,
+                       "metadata": { "shortName": null } },
+                "2": { "id": "2", "name": "Two", "chains": ["eip155:1", "eip155:10"],
+                       "image_id": "i", "mobile": { "native": "two://", "universal": null },
+                       "desktop": null, "metadata": { "shortName": null } }
+            },
+            "count": 2,
+            "total": 150
+        }))
+        .unwrap();
+
+        let query = WalletQuery { chains: vec![10], ..Default::default() };
+        let page = client.to_page(response, &query, WalletSource::Explorer);
+        assert_eq!(page.wallets.len(), 1);
+        assert_eq!(page.wallets[0].id, "2");
+        assert_eq!((page.count, page.total), (2, 150));
+        assert!(page.has_more(DEFAULT_PAGE_SIZE));
+    }
+
+    #[test]
+    fn test_offline_page_lists_bundled_wallets() {
+        let client = ExplorerClient::new("project");
+
+        let page = client.offline_page(&WalletQuery { entries: 2, ..Default::default() });
+        assert_eq!(page.source, WalletSource::Bundled);
+        assert_eq!(page.count, 2);
+        assert!(page.total > 2 && page.has_more(2));
+        assert!(page.wallets.iter().all(|w| w.project_id == "project"));
+
+        let query = WalletQuery { search: Some("rain".to_string()), ..Default::default() };
+        let page = client.offline_page(&query);
+        assert_eq!(page.total, 1);
+        assert_eq!(page.wallets[0].name, "Rainbow");
+    }
+}
@@ -0,0 +1,411 @@
+pub mod cache;
+pub mod client;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+
+/// Snapshot of popular wallets listed when explorer is unreachable, in explorer response format
+const BUNDLED_WALLETS: &str = include_str!("wallets.json");
+/// Characters left intact by JavaScript's `encodeURIComponent`
+const URI_COMPONENT_SAFE: &[u8] = b"-_.!~*'()";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExplorerResponse {
+    /// Wallets in explorer's ranking order
+    pub listings: IndexMap<String, WalletData>,
+    pub count: u32,
+    pub total: u32,
+}
+
+impl ExplorerResponse {
+    /// Wallets bundled with the library
+    pub fn bundled() -> Self {
+        serde_json::from_str(BUNDLED_WALLETS).expect("Bundled wallets list should be correct")
+    }
+
+    pub fn parse_wallets(&self, project_id: &str) -> Vec<WalletDescription> {
+        let mut wallets: Vec<WalletDescription> = Vec::new();
+        for wallet in self.listings.values() {
+            if let Ok(mut w) = TryInto::<WalletDescription>::try_into(wallet) {
+                w.project_id = project_id.to_string();
+                wallets.push(w)
+            }
+        }
+        wallets
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WalletData {
+    pub id: String,
+    pub name: String,
+    pub chains: Vec<String>,
+    pub image_id: String,
+    pub mobile: Option<LinkSet>,
+    pub desktop: Option<LinkSet>,
+    pub metadata: WalletMetadata,
+    #[serde(default)]
+    pub rdns: Option<String>,
+    #[serde(default)]
+    pub injected: Option<Vec<InjectedId>>,
+    #[serde(default)]
+    pub app: Option<AppLinks>,
+}
+
+pub enum ExplorerError {
+    BadWallet,
+}
+
+impl TryInto<WalletDescription> for &WalletData {
+    type Error = ExplorerError;
+
+    fn try_into(self) -> Result<WalletDescription, Self::Error> {
+        let chains = self
+            .chains
+            .iter()
+            .filter_map(|c| c.strip_prefix("eip155:").and_then(|id| id.parse::<u64>().ok()))
+            .collect::<Vec<_>>();
+        let mobile_schema = self.mobile.as_ref().and_then(|l| non_empty(&l.native));
+        let mobile_universal = self.mobile.as_ref().and_then(|l| non_empty(&l.universal));
+        let desktop_schema = self.desktop.as_ref().and_then(|l| non_empty(&l.native));
+        let desktop_universal = self.desktop.as_ref().and_then(|l| non_empty(&l.universal));
+
+        if mobile_schema.is_none()
+            && mobile_universal.is_none()
+            && desktop_schema.is_none()
+            && desktop_universal.is_none()
+        {
+            return Err(ExplorerError::BadWallet);
+        }
+
+        Ok(WalletDescription {
+            id: self.id.clone(),
+            short_name: self.metadata.short_name.clone().unwrap_or(self.name.clone()),
+            name: self.name.clone(),
+            chains,
+            image_id: self.image_id.clone(),
+            project_id: "".to_owned(),
+            desktop_schema,
+            desktop_universal,
+            mobile_schema,
+            mobile_universal,
+            rdns: self.rdns.clone().filter(|r| !r.is_empty()),
+            injected_flags: self
+                .injected
+                .iter()
+                .flatten()
+                .filter(|i| i.namespace == "eip155")
+                .map(|i| i.injected_id.clone())
+                .collect(),
+            app: self.app.clone().unwrap_or_default(),
+        })
+    }
+}
+
+fn non_empty(link: &Option<String>) -> Option<String> {
+    link.as_ref().filter(|l| !l.is_empty()).cloned()
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LinkSet {
+    pub native: Option<String>,
+    pub universal: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletMetadata {
+    pub short_name: Option<String>,
+}
+
+/// Way wallet's browser extension is recognized in given namespace
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InjectedId {
+    pub namespace: String,
+    pub injected_id: String,
+}
+
+/// Store and download links of the wallet
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AppLinks {
+    pub browser: Option<String>,
+    pub ios: Option<String>,
+    pub android: Option<String>,
+    pub chrome: Option<String>,
+    pub firefox: Option<String>,
+    pub safari: Option<String>,
+    pub edge: Option<String>,
+}
+
+/// Platform the dApp is running on, deciding which wallet link should be used
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Platform {
+    Ios,
+    Android,
+    Desktop,
+}
+
+impl Platform {
+    pub fn is_mobile(&self) -> bool {
+        matches!(self, Platform::Ios | Platform::Android)
+    }
+
+    /// Detects platform from browser's user agent
+    pub fn detect() -> Self {
+        let Ok(navigator) =
+            js_sys::Reflect::get(&JsValue::from(gloo_utils::window()), &"navigator".into())
+        else {
+            return Platform::Desktop;
+        };
+        let touch_points = js_sys::Reflect::get(&navigator, &"maxTouchPoints".into())
+            .ok()
+            .and_then(|points| points.as_f64())
+            .unwrap_or_default();
+        js_sys::Reflect::get(&navigator, &"userAgent".into())
+            .ok()
+            .and_then(|agent| agent.as_string())
+            .map(|agent| Self::from_user_agent(&agent, touch_points as u32))
+            .unwrap_or(Platform::Desktop)
+    }
+
+    /// Recognizes platform by user agent and number of supported touch points. iPadOS 13+
+    /// presents itself as desktop Safari, but unlike Macs it supports multi-touch.
+    pub fn from_user_agent(agent: &str, max_touch_points: u32) -> Self {
+        let agent = agent.to_lowercase();
+        let ios = agent.contains("iphone") || agent.contains("ipad") || agent.contains("ipod");
+        if ios || (agent.contains("macintosh") && max_touch_points > 1) {
+            Platform::Ios
+        } else if agent.contains("android") {
+            Platform::Android
+        } else {
+            Platform::Desktop
+        }
+    }
+}
+
+pub enum ImageSize {
+    Small,
+    Medium,
+    Large,
+}
+
+impl ImageSize {
+    fn mark(&self) -> &'static str {
+        match self {
+            ImageSize::Small => "sm",
+            ImageSize::Medium => "md",
+            ImageSize::Large => "lg",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WalletDescription {
+    pub id: String,
+    pub short_name: String,
+    pub name: String,
+    pub chains: Vec<u64>,
+    pub image_id: String,
+    pub project_id: String,
+    pub desktop_schema: Option<String>,
+    pub desktop_universal: Option<String>,
+    pub mobile_schema: Option<String>,
+    pub mobile_universal: Option<String>,
+    /// EIP-6963 reverse DNS name of wallet's browser extension
+    #[serde(default)]
+    pub rdns: Option<String>,
+    /// Flags wallet's browser extension sets on `window.ethereum`, i.e. `isMetaMask`
+    #[serde(default)]
+    pub injected_flags: Vec<String>,
+    #[serde(default)]
+    pub app: AppLinks,
+}
+
+impl WalletDescription {
+    /// Returns logo url from default explorer, see `ExplorerClient::logo_url` for other ones
+    pub fn get_image(&self, size: ImageSize) -> String {
+        format!(
+            "{}v3/logo/{}/{}?projectId={}",
+            client::DEFAULT_EXPLORER_URL,
+            size.mark(),
+            self.image_id,
+            self.project_id
+        )
+    }
+
+    /// Returns link bringing the wallet to front, i.e. to approve pending request
+    pub fn open_link(&self, platform: Platform) -> Option<String> {
+        self.link_base(platform).map(|base| format!("{base}wc"))
+    }
+
+    /// Returns link opening the wallet with given WalletConnect pairing uri. On iOS universal
+    /// links are preferred, as native schemes ask the user for confirmation, everywhere else
+    /// native schemes go first.
+    pub fn pairing_link(&self, uri: &str, platform: Platform) -> Option<String> {
+        self.link_base(platform).map(|base| format!("{base}wc?uri={}", encode_uri_component(uri)))
+    }
+
+    /// Returns link installing the wallet on given platform, browser extensions are offered on
+    /// desktop
+    pub fn install_link(&self, platform: Platform) -> Option<String> {
+        let app = &self.app;
+        let link = match platform {
+            Platform::Ios => app.ios.clone(),
+            Platform::Android => app.android.clone(),
+            Platform::Desktop => app
+                .chrome
+                .clone()
+                .or_else(|| app.firefox.clone())
+                .or_else(|| app.edge.clone())
+                .or_else(|| app.safari.clone()),
+        };
+        link.or_else(|| app.browser.clone()).filter(|l| !l.is_empty())
+    }
+
+    /// Checks if wallet can be opened on given platform
+    pub fn supports(&self, platform: Platform) -> bool {
+        self.link_base(platform).is_some()
+    }
+
+    fn link_base(&self, platform: Platform) -> Option<String> {
+        let native = |schema: &Option<String>| schema.as_deref().map(native_base);
+        let universal = |link: &Option<String>| link.as_deref().map(universal_base);
+
+        match platform {
+            Platform::Ios => {
+                universal(&self.mobile_universal).or_else(|| native(&self.mobile_schema))
+            }
+            Platform::Android => {
+                native(&self.mobile_schema).or_else(|| universal(&self.mobile_universal))
+            }
+            Platform::Desktop => {
+                native(&self.desktop_schema).or_else(|| universal(&self.desktop_universal))
+            }
+        }
+    }
+}
+
+fn native_base(schema: &str) -> String {
+    let mut base = if schema.contains("://") {
+        schema.to_string()
+    } else {
+        format!("{}://", schema.replace(['/', ':'], ""))
+    };
+    if !base.ends_with('/') {
+        base.push('/');
+    }
+    base
+}
+
+fn universal_base(link: &str) -> String {
+    format!("{}/", link.trim_end_matches('/'))
+}
+
+fn encode_uri_component(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || URI_COMPONENT_SAFE.contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URI: &str = "wc:7f6e504b@2?relay-protocol=irn&symKey=587d5484";
+    const ENCODED: &str = "wc%3A7f6e504b%402%3Frelay-protocol%3Dirn%26symKey%3D587d5484";
+
+    fn wallet() -> WalletDescription {
+        WalletDescription {
+            id: "id".to_string(),
+            short_name: "Wallet".to_string(),
+            name: "Wallet".to_string(),
+            chains: vec![1],
+            image_id: "image".to_string(),
+            project_id: "project".to_string(),
+            desktop_schema: Some("wallet:".to_string()),
+            desktop_universal: None,
+            mobile_schema: Some("wallet://".to_string()),
+            mobile_universal: Some("https://wallet.app/".to_string()),
+            rdns: None,
+            injected_flags: vec![],
+            app: AppLinks::default(),
+        }
+    }
+
+    #[test]
+    fn test_pairing_link_prefers_universal_link_on_ios() {
+        let wallet = wallet();
+
+        assert_eq!(
+            wallet.pairing_link(URI, Platform::Ios).unwrap(),
+            format!("https://wallet.app/wc?uri={ENCODED}")
+        );
+        assert_eq!(
+            wallet.pairing_link(URI, Platform::Android).unwrap(),
+            format!("wallet://wc?uri={ENCODED}")
+        );
+        assert_eq!(
+            wallet.pairing_link(URI, Platform::Desktop).unwrap(),
+            format!("wallet://wc?uri={ENCODED}")
+        );
+    }
+
+    #[test]
+    fn test_pairing_link_falls_back_to_available_link() {
+        let wallet = WalletDescription { mobile_universal: None, desktop_schema: None, ..wallet() };
+
+        assert_eq!(
+            wallet.pairing_link(URI, Platform::Ios).unwrap(),
+            format!("wallet://wc?uri={ENCODED}")
+        );
+        assert!(!wallet.supports(Platform::Desktop));
+    }
+
+    #[test]
+    fn test_open_link() {
+        let wallet = wallet();
+
+        assert_eq!(wallet.open_link(Platform::Ios).unwrap(), "https://wallet.app/wc");
+        assert_eq!(wallet.open_link(Platform::Android).unwrap(), "wallet://wc");
+    }
+
+    #[test]
+    fn test_parsed_wallets_keep_explorer_order() {
+        let listing = |id: &str| {
+            format!(
+                r#""{id}": {{ "id": "{id}", "name": "{id}", "chains": [], "image_id": "i",
+                    "mobile": {{ "native": "{id}://", "universal": null }}, "desktop": null,
+                    "metadata": {{ "shortName": null }} }}"#
+            )
+        };
+        let ids = ["c", "a", "d", "b"];
+        let listings = ids.map(listing).join(",");
+        let response = format!(r#"{{ "listings": {{ {listings} }}, "count": 4, "total": 4 }}"#);
+        let response: ExplorerResponse = serde_json::from_str(&response).unwrap();
+
+        let wallets = response.parse_wallets("project");
+        assert_eq!(wallets.iter().map(|w| w.id.as_str()).collect::<Vec<_>>(), ids);
+    }
+
+    #[test]
+    fn test_platform_from_user_agent() {
+        let ios = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) AppleWebKit/605.1.15";
+        let android = "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36";
+        let desktop = "Mozilla/5.0 (X11; Linux x86_64; rv:120.0) Gecko/20100101 Firefox/120.0";
+        let mac = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15";
+
+        assert_eq!(Platform::from_user_agent(ios, 5), Platform::Ios);
+        assert_eq!(Platform::from_user_agent(android, 5), Platform::Android);
+        assert_eq!(Platform::from_user_agent(desktop, 0), Platform::Desktop);
+        assert_eq!(Platform::from_user_agent(mac, 0), Platform::Desktop);
+        // iPadOS 13+ reports itself as Mac
+        assert_eq!(Platform::from_user_agent(mac, 5), Platform::Ios);
+    }
+}
@@ -1,22 +1,53 @@
 use std::rc::Rc;
 
-use crate::{Ethereum as Ethers, EthereumBuilder, EthereumError, Event, WalletType};
+use crate::{
+    balance::Balances,
+    chains::Chain,
+    connection::ConnectionState,
+    ens::EnsProfile,
+    prompt::PromptRequest,
+    qr::{self, QrOptions},
+    walletconnect::{open_link, pairing::PairingInfo},
+    Ethereum as Ethers, EthereumBuilder, EthereumError, Event, WalletType,
+};
 use ethers::{
     providers::Provider,
     types::{Address, Signature},
 };
 use leptos::*;
-use log::{debug, error};
+use log::{debug, error, warn};
 use serde::Serialize;
 use url::Url;
 
 /// Structure informing about current ethereum connection state
 #[derive(Debug, Clone)]
 pub struct EthereumState {
-    pub connected: bool,
     pub accounts: Option<Vec<Address>>,
+    pub active_account: Option<Address>,
+    pub balances: Balances,
     pub chain_id: Option<u64>,
-    pub pairing_url: Option<String>,
+    pub pairing: Option<PairingInfo>,
+    pub connection_state: ConnectionState,
+    pub prompt: Option<PromptRequest>,
+    /// Link to paired mobile wallet waiting for user's approval of a request
+    pub wallet_link: Option<String>,
+}
+
+impl EthereumState {
+    /// Checks if wallet can be used, possibly after pending switch or reconnection finishes
+    pub fn is_connected(&self) -> bool {
+        self.connection_state.is_connected()
+    }
+
+    /// Gets WalletConnect pairing waiting to be approved by the wallet
+    pub fn pairing(&self) -> Option<&PairingInfo> {
+        self.pairing.as_ref().filter(|_| self.connection_state == ConnectionState::AwaitingPairing)
+    }
+
+    /// Gets uri of WalletConnect pairing waiting to be approved by the wallet
+    pub fn pairing_url(&self) -> Option<&String> {
+        self.pairing().and_then(|p| p.uri.as_ref())
+    }
 }
 
 /// Main component for ethereum connections. Define it as your webiste root to get the access
@@ -30,6 +61,27 @@ pub fn Ethereum(children: Children) -> impl IntoView {
     children()
 }
 
+/// QR code of current WalletConnect pairing url. Renders nothing when there is no pairing pending.
+#[component]
+pub fn PairingQr(
+    #[prop(optional)] options: QrOptions,
+    #[prop(optional, into)] class: String,
+) -> impl IntoView {
+    let ethereum = expect_context::<EthereumContext>();
+
+    let svg = move || {
+        ethereum.pairing_url().and_then(|url| match qr::to_svg(&url, &options) {
+            Ok(svg) => Some(svg),
+            Err(err) => {
+                error!("Could not render pairing QR code {err:?}");
+                None
+            }
+        })
+    };
+
+    view! { <div class=class inner_html=move || svg().unwrap_or_default()></div> }
+}
+
 /// Ethereum context for your website
 #[derive(Clone, Debug)]
 pub struct EthereumContext {
@@ -61,16 +113,68 @@ impl EthereumContext {
         self.inner.accounts()
     }
 
+    /// Gets currently selected account of connected wallet
+    pub fn active_account(&self) -> Option<Address> {
+        self.inner.active_account()
+    }
+
+    /// Selects account from the list of connected wallet accounts as the active one
+    pub fn set_active_account(&self, account: Address) {
+        self.inner.set_active_account(account);
+    }
+
+    /// Gets latest known native and watched ERC-20 token balances of the active account
+    pub fn balances(&self) -> Balances {
+        self.inner.balances()
+    }
+
+    /// Starts tracking balance of ERC-20 token for the active account
+    pub fn watch_token(&self, token: Address) {
+        self.inner.watch_token(token);
+    }
+
     /// Gets current chain id of connected wallet
     pub fn chain_id(&self) -> Option<u64> {
         self.inner.chain_id()
     }
 
+    /// Gets description of the chain connected wallet is using
+    pub fn chain(&self) -> Option<Chain> {
+        self.inner.chain()
+    }
+
     /// Returns current pairing url if wallet connect connection is awaiting to be established
     pub fn pairing_url(&self) -> Option<String> {
         self.inner.pairing_url()
     }
 
+    pub fn pairing(&self) -> Option<PairingInfo> {
+        self.inner.pairing()
+    }
+
+    /// Gets current state of the connection
+    pub fn connection_state(&self) -> ConnectionState {
+        self.inner.connection_state()
+    }
+
+    /// Gets wallet request currently waiting for user's approval
+    pub fn prompt(&self) -> Option<PromptRequest> {
+        self.inner.prompt()
+    }
+
+    /// Gets link to paired mobile wallet waiting for user's approval of a request, see
+    /// `EthereumBuilder::navigate_to_wallet`
+    pub fn wallet_link(&self) -> Option<String> {
+        self.inner.wallet_link()
+    }
+
+    /// Opens paired mobile wallet waiting for user's approval, i.e. on button click
+    pub fn open_wallet(&self) {
+        if let Some(link) = self.wallet_link() {
+            open_link(&link);
+        }
+    }
+
     /// Gets a provider you can feed to ethers constructors to start interaction with wallet and
     /// the network
     pub fn provider(&self) -> Provider<Ethers> {
@@ -98,10 +202,14 @@ pub(crate) struct EthereumInnerContext {
 impl EthereumInnerContext {
     pub(crate) fn new() -> Self {
         let (state, set_state) = create_signal(EthereumState {
-            connected: false,
             accounts: None,
+            active_account: None,
+            balances: Balances::default(),
             chain_id: None,
-            pairing_url: None,
+            pairing: None,
+            connection_state: ConnectionState::default(),
+            prompt: None,
+            wallet_link: None,
         });
 
         let mut builder = EthereumBuilder::new();
@@ -112,6 +220,16 @@ impl EthereumInnerContext {
         if let Some(rpc_url) = std::option_env!("RPC_URL") {
             builder.rpc_node(rpc_url);
         }
+        if let Some(relay_url) = std::option_env!("RELAY_URL") {
+            match Url::parse(relay_url) {
+                Ok(relay_url) => {
+                    builder.wc_relay_url(relay_url);
+                }
+                Err(err) => {
+                    error!("Incorrect relay url in variable RELAY_URL, using default {err:?}")
+                }
+            }
+        }
 
         let app_url = if let Some(app_url) = std::option_env!("APP_URL") {
             app_url
@@ -120,14 +238,10 @@ impl EthereumInnerContext {
         };
 
         let ethereum = builder
-            .url(
-                Url::parse(app_url).expect(
-                    &format!(
-                        "Correct app url in variable APP_URL is not provided. '{:?}'",
-                        std::option_env!("APP_URL")
-                    ),
-                ),
-            )
+            .url(Url::parse(app_url).expect(&format!(
+                "Correct app url in variable APP_URL is not provided. '{:?}'",
+                std::option_env!("APP_URL")
+            )))
             .build();
 
         let (ethers, set_ethers) = create_signal(ethereum);
@@ -167,8 +281,7 @@ impl EthereumInnerContext {
     }
 
     pub fn is_connected(&self) -> bool {
-        let state = self.state.get();
-        state.connected
+        self.state.with(|state| state.is_connected())
     }
 
     pub fn accounts(&self) -> Option<Vec<Address>> {
@@ -176,14 +289,62 @@ impl EthereumInnerContext {
         state.accounts
     }
 
+    pub fn active_account(&self) -> Option<Address> {
+        let state = self.state.get();
+        state.active_account
+    }
+
+    pub fn set_active_account(&self, account: Address) {
+        let eth = self.ethers.get();
+        spawn_local(async move {
+            if let Err(err) = eth.set_active_account(account).await {
+                error!("Cannot select active account {err:?}");
+            }
+        });
+    }
+
+    pub fn balances(&self) -> Balances {
+        let state = self.state.get();
+        state.balances
+    }
+
+    pub fn watch_token(&self, token: Address) {
+        let eth = self.ethers.get();
+        spawn_local(async move {
+            if let Err(err) = eth.watch_token(token).await {
+                error!("Cannot watch token balance {err:?}");
+            }
+        });
+    }
+
     pub fn chain_id(&self) -> Option<u64> {
         let state = self.state.get();
         state.chain_id
     }
 
+    pub fn chain(&self) -> Option<Chain> {
+        let chain_id = self.state.get().chain_id?;
+        self.ethers.with(|eth| eth.chains.get(chain_id).cloned())
+    }
+
     pub fn pairing_url(&self) -> Option<String> {
-        let state = self.state.get();
-        state.pairing_url
+        self.state.with(|state| state.pairing_url().cloned())
+    }
+
+    pub fn pairing(&self) -> Option<PairingInfo> {
+        self.state.with(|state| state.pairing().cloned())
+    }
+
+    pub fn connection_state(&self) -> ConnectionState {
+        self.state.get().connection_state
+    }
+
+    pub fn prompt(&self) -> Option<PromptRequest> {
+        self.state.get().prompt
+    }
+
+    pub fn wallet_link(&self) -> Option<String> {
+        self.state.get().wallet_link
     }
 
     pub fn provider(&self) -> Provider<Ethers> {
@@ -203,26 +364,51 @@ impl EthereumInnerContext {
 
 async fn run(eth: Ethers, set_state: WriteSignal<EthereumState>) {
     let mut keep_looping = true;
-    let mut state =
-        EthereumState { connected: false, accounts: None, chain_id: None, pairing_url: None };
+    let mut state = EthereumState {
+        accounts: None,
+        active_account: None,
+        balances: Balances::default(),
+        chain_id: None,
+        pairing: None,
+        connection_state: ConnectionState::default(),
+        prompt: None,
+        wallet_link: None,
+    };
 
     while keep_looping {
         match eth.next().await {
             Ok(Some(event)) => match event {
-                Event::ConnectionWaiting(url) => {
-                    state.pairing_url = Some(url);
+                Event::ConnectionWaiting(info) => {
+                    state.pairing = Some(info);
                     set_state.set(state.clone());
                 }
                 Event::Connected => {
-                    state.connected = true;
-                    state.pairing_url = None;
+                    state.pairing = None;
+                    set_state.set(state.clone());
+                }
+                Event::Disconnected | Event::SessionExpired => {
+                    state.wallet_link = None;
                     set_state.set(state.clone());
                 }
-                Event::Disconnected => {
-                    state.connected = false;
+                Event::Broken(reason) => warn!("Connection broken: {reason}"),
+                Event::Reconnecting(attempt) => debug!("Reconnecting, attempt {attempt}"),
+                Event::Reconnected => debug!("Reconnected"),
+                Event::ConnectionStateChanged(_) => {}
+                Event::PromptOpened(request) => {
+                    state.prompt = Some(request);
                     set_state.set(state.clone());
                 }
-                Event::Broken => {}
+                Event::PromptClosed(_) => {
+                    state.prompt = eth.current_prompt();
+                    state.wallet_link = None;
+                    set_state.set(state.clone());
+                }
+                Event::SessionExpiring(expiry) => {
+                    warn!("WalletConnect session expires at {expiry}")
+                }
+                Event::SessionExtended(expiry) => {
+                    debug!("WalletConnect session extended until {expiry}")
+                }
                 Event::ChainIdChanged(chain_id) => {
                     state.chain_id = chain_id;
                     set_state.set(state.clone());
@@ -231,6 +417,19 @@ async fn run(eth: Ethers, set_state: WriteSignal<EthereumState>) {
                     state.accounts = accounts;
                     set_state.set(state.clone());
                 }
+                Event::ActiveAccountChanged(account) => {
+                    state.active_account = account;
+                    set_state.set(state.clone());
+                }
+                Event::BalancesChanged(balances) => {
+                    state.balances = balances;
+                    set_state.set(state.clone());
+                }
+                Event::NewBlock(_) => {}
+                Event::OpenWallet(link) => {
+                    state.wallet_link = Some(link);
+                    set_state.set(state.clone());
+                }
             },
             Ok(None) => {}
             Err(err) => {
@@ -238,6 +437,38 @@ async fn run(eth: Ethers, set_state: WriteSignal<EthereumState>) {
                 error!("Error on fetching event message {err:?}");
             }
         }
+        // State changes are read from the connection itself, as their events may be dropped
+        // once the queue is full
+        let connection = eth.connection_state();
+        if state.connection_state != connection {
+            state.connection_state = connection;
+            set_state.set(state.clone());
+        }
     }
     debug!("Listener loop ended");
 }
+
+/// Resolves ENS name and avatar of given address using ethereum from `EthereumContext`
+pub fn use_ens(address: Signal<Option<Address>>) -> Resource<Option<Address>, Option<EnsProfile>> {
+    let context = use_context::<EthereumContext>()
+        .expect("No ethereum found. You must wrap your components in an <Ethereum /> component");
+
+    create_local_resource(
+        move || address.get(),
+        move |address| {
+            let eth = context.inner.ethers.get_untracked();
+            async move {
+                match address {
+                    Some(address) => match eth.lookup_ens(address).await {
+                        Ok(profile) => Some(profile),
+                        Err(err) => {
+                            error!("ENS lookup failed {err:?}");
+                            None
+                        }
+                    },
+                    None => None,
+                }
+            }
+        },
+    )
+}
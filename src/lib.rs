@@ -2,51 +2,90 @@
 //! library.
 #![doc = include_str!("../README.md")]
 
+pub mod balance;
+pub mod block;
+pub mod chains;
+pub mod connection;
+pub mod ens;
 pub mod explorer;
+pub mod prompt;
+pub mod qr;
+pub mod reconnect;
+pub mod wallets;
 
 mod eip1193;
 mod event;
+mod methods;
 
 #[cfg(feature = "leptos")]
 pub mod leptos;
 
-mod walletconnect;
+pub mod walletconnect;
 #[cfg(feature = "yew")]
 pub mod yew;
 
 use async_trait::async_trait;
+use balance::{fetch_balances, BalanceWatcher, Balances};
+use block::{fetch_new_block, page_visible, BlockHeader, BlockWatcher, PollInterval};
+use chains::{Chain, ChainRegistry};
+use connection::{ConnectionMachine, ConnectionState, Transition};
 use eip1193::{error::Eip1193Error, Eip1193};
+use ens::{EnsProfile, EnsResolver};
 use ethers::{
     providers::{Http, HttpClientError, JsonRpcClient, JsonRpcError, ProviderError, RpcError},
     types::{Address, Signature, SignatureError, U256},
     utils::ConversionError,
 };
-use gloo_storage::{LocalStorage, Storage};
+use explorer::{
+    cache::DEFAULT_CACHE_TTL_SECS,
+    client::{ExplorerClient, WalletPage, WalletQuery},
+    ExplorerResponse, ImageSize, Platform,
+};
+use futures::{future, stream, Stream, StreamExt};
+use gloo::timers::future::TimeoutFuture;
+use gloo_storage::{errors::StorageError, LocalStorage, Storage};
 use gloo_utils::format::JsValueSerdeExt;
 use hex::FromHexError;
-use log::{debug, error};
+use log::{debug, error, warn};
+use methods::needs_user_action;
+use prompt::{PromptQueue, PromptRequest};
+use reconnect::{ReconnectPolicy, Reconnection};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
 use std::{
+    collections::BTreeMap,
     fmt::{Debug, Formatter, Result as FmtResult},
     str::FromStr,
-    sync::Arc,
+    sync::{Arc, RwLock},
 };
 use thiserror::Error;
 use tokio::sync::{
     mpsc::{channel, Receiver, Sender},
-    Mutex,
+    watch, Mutex,
 };
 use url::Url;
 use walletconnect_client::{
     prelude::{Metadata, WalletConnectError},
-    WalletConnect, WalletConnectState,
+    State as WalletConnectClientState, WalletConnect, WalletConnectState,
+};
+use wallets::{
+    prefer_recent, wallet_options, InjectedWallets, RecentWallet, RecentWallets, WalletAction,
+    WalletOption,
 };
 use wasm_bindgen_futures::spawn_local;
 
 const STATUS_KEY: &str = "ETHERS_WEB_STATE";
+/// Time given to the wallet to update session after switching chain
+const CHAIN_APPROVAL_TIMEOUT_MS: u32 = 60_000;
 
 use crate::event::WalletEvent;
-use walletconnect::WalletConnectProvider;
+use walletconnect::{
+    expiry::{ExpiryStatus, LoopGuard, MAX_CHECK_INTERVAL_SECS, WALLET_ANSWER_TIMEOUT_MS},
+    namespaces::{GrantedNamespace, SessionProposal},
+    pairing::PairingInfo,
+    sessions::{SessionStore, StoredPairing, StoredSession},
+    WalletConnectProvider, WalletRedirect,
+};
 use walletconnect_client::prelude::Event as WalletConnectEvent;
 
 /// Ethereum builder for Ethereum object
@@ -56,8 +95,21 @@ pub struct EthereumBuilder {
     pub description: String,
     pub url: Url,
     pub wc_project_id: Option<String>,
+    pub wc_relay_url: Option<Url>,
+    pub wc_verify_url: Option<Url>,
+    pub explorer_url: Option<Url>,
+    pub explorer_cache_ttl: Option<u64>,
     pub icons: Vec<String>,
     pub rpc_node: Option<String>,
+    pub mainnet_rpc_node: Option<String>,
+    pub ipfs_gateway: Option<String>,
+    pub watch_balances: bool,
+    pub watched_tokens: Vec<Address>,
+    pub watch_blocks: bool,
+    pub navigate_to_wallet: bool,
+    pub chains: ChainRegistry,
+    pub wc_proposal: SessionProposal,
+    pub reconnect_policy: ReconnectPolicy,
 }
 
 impl Default for EthereumBuilder {
@@ -75,8 +127,21 @@ impl EthereumBuilder {
             description: "An example dApp written in Rust".to_string(),
             url: Url::parse("https://github.com/quay-rs/ethers-web").unwrap(),
             wc_project_id: None,
+            wc_relay_url: None,
+            wc_verify_url: None,
+            explorer_url: None,
+            explorer_cache_ttl: Some(DEFAULT_CACHE_TTL_SECS),
             icons: Vec::new(),
             rpc_node: None,
+            mainnet_rpc_node: None,
+            ipfs_gateway: None,
+            watch_balances: false,
+            watched_tokens: Vec::new(),
+            watch_blocks: false,
+            navigate_to_wallet: false,
+            chains: ChainRegistry::bundled(),
+            wc_proposal: SessionProposal::default(),
+            reconnect_policy: ReconnectPolicy::default(),
         }
     }
 
@@ -110,12 +175,123 @@ impl EthereumBuilder {
         self
     }
 
+    /// Setting WalletConnect relay, [`walletconnect::DEFAULT_RELAY_URL`] is used by default
+    pub fn wc_relay_url(&mut self, relay_url: Url) -> &Self {
+        self.wc_relay_url = Some(relay_url);
+        self
+    }
+
+    /// Setting WalletConnect verify server announced in dApp's metadata
+    pub fn wc_verify_url(&mut self, verify_url: Url) -> &Self {
+        self.wc_verify_url = Some(verify_url);
+        self
+    }
+
+    /// Setting WalletConnect explorer, i.e. self-hosted or proxied one
+    pub fn explorer_url(&mut self, explorer_url: Url) -> &Self {
+        self.explorer_url = Some(explorer_url);
+        self
+    }
+
+    /// Setting time explorer responses are served from local storage without revalidation
+    pub fn explorer_cache_ttl(&mut self, ttl_secs: u64) -> &Self {
+        self.explorer_cache_ttl = Some(ttl_secs);
+        self
+    }
+
+    /// Disabling caching of explorer responses
+    pub fn no_explorer_cache(&mut self) -> &Self {
+        self.explorer_cache_ttl = None;
+        self
+    }
+
+    /// Adding CAIP-2 chains, methods and events WalletConnect wallet has to support to approve
+    /// the session. Without any namespaces configured, default methods are requested for default
+    /// chain. Only `eip155` chains can be proposed, connecting fails otherwise.
+    pub fn wc_required_namespace(
+        &mut self,
+        chains: &[&str],
+        methods: &[&str],
+        events: &[&str],
+    ) -> &Self {
+        self.wc_proposal.require(chains, methods, events);
+        self
+    }
+
+    /// Adding CAIP-2 chains, methods and events WalletConnect wallet may support
+    pub fn wc_optional_namespace(
+        &mut self,
+        chains: &[&str],
+        methods: &[&str],
+        events: &[&str],
+    ) -> &Self {
+        self.wc_proposal.request_optional(chains, methods, events);
+        self
+    }
+
     /// Setting RPC node working together with WalletConnect connection for non-signer interactions
     pub fn rpc_node(&mut self, rpc_node: &str) -> &Self {
         self.rpc_node = Some(rpc_node.to_string());
         self
     }
 
+    /// Setting mainnet RPC node used for ENS resolution, regardless of the chain wallet is
+    /// connected to
+    pub fn mainnet_rpc_node(&mut self, rpc_node: &str) -> &Self {
+        self.mainnet_rpc_node = Some(rpc_node.to_string());
+        self
+    }
+
+    /// Setting IPFS gateway used to resolve ENS avatars
+    pub fn ipfs_gateway(&mut self, gateway: &str) -> &Self {
+        self.ipfs_gateway = Some(gateway.to_string());
+        self
+    }
+
+    /// Enabling native balance tracking of the active account
+    pub fn watch_balances(&mut self) -> &Self {
+        self.watch_balances = true;
+        self
+    }
+
+    /// Adding ERC-20 token which balance of the active account should be tracked
+    pub fn watch_token(&mut self, token: Address) -> &Self {
+        self.watched_tokens.push(token);
+        self
+    }
+
+    /// Enabling new block polling while the wallet is connected
+    pub fn watch_blocks(&mut self) -> &Self {
+        self.watch_blocks = true;
+        self
+    }
+
+    /// Navigating directly to the paired mobile wallet on requests requiring user's approval,
+    /// instead of emitting `Event::OpenWallet` with a link to be opened by the user
+    pub fn navigate_to_wallet(&mut self) -> &Self {
+        self.navigate_to_wallet = true;
+        self
+    }
+
+    /// Setting backoff and retry budget of reconnecting broken connections, see
+    /// `ReconnectPolicy::disabled` to disconnect right away
+    pub fn reconnect_policy(&mut self, policy: ReconnectPolicy) -> &Self {
+        self.reconnect_policy = policy;
+        self
+    }
+
+    /// Adding chain to the registry of known chains or replacing bundled one
+    pub fn add_chain(&mut self, chain: Chain) -> &Self {
+        self.chains.insert(chain);
+        self
+    }
+
+    /// Adding chains described in ethereum-lists `chains` JSON format to the registry
+    pub fn import_chains(&mut self, json: &str) -> Result<&Self, serde_json::Error> {
+        self.chains.import_ethereum_lists(json)?;
+        Ok(self)
+    }
+
     /// Setting dApp icon url
     pub fn add_icon(&mut self, icon_url: &str) -> &Self {
         self.icons.push(icon_url.to_string());
@@ -124,20 +300,12 @@ impl EthereumBuilder {
 
     /// Building final Ethereum object
     pub fn build(&self) -> Ethereum {
-        Ethereum::new(
-            self.chain_id,
-            self.name.clone(),
-            self.description.clone(),
-            self.url.clone(),
-            self.wc_project_id.clone(),
-            self.icons.clone(),
-            self.rpc_node.clone(),
-        )
+        Ethereum::new(self)
     }
 }
 
 /// Available wallet types
-#[derive(Clone, Debug, Copy)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum WalletType {
     Injected,
     WalletConnect,
@@ -155,6 +323,30 @@ pub enum EthereumError {
     #[error("Already connected")]
     AlreadyConnected,
 
+    #[error("Account {0:?} is not available in connected wallet")]
+    UnknownAccount(Address),
+
+    #[error("Chain {0} is unknown")]
+    UnknownChain(u64),
+
+    #[error("Wallet has not approved accounts on chain {0}")]
+    ChainNotApproved(u64),
+
+    #[error("WalletConnect session or pairing {0} is unknown")]
+    UnknownSession(String),
+
+    #[error("Wallet {0} cannot be opened on this platform")]
+    UnsupportedWallet(String),
+
+    #[error("WalletConnect gave no pairing uri to open the wallet with")]
+    MissingPairingUri,
+
+    #[error("Wallet did not answer in time")]
+    WalletTimeout,
+
+    #[error("Wallet no longer authorizes the dApp")]
+    Unauthorized,
+
     #[error(transparent)]
     ConversionError(#[from] ConversionError),
 
@@ -181,6 +373,31 @@ pub enum EthereumError {
 
     #[error(transparent)]
     ReqwestError(#[from] reqwest::Error),
+
+    #[error(transparent)]
+    StorageError(#[from] StorageError),
+}
+
+/// Result of restoring stored connection
+#[derive(Debug)]
+pub enum RestoreOutcome {
+    /// There was no connection stored
+    NotStored,
+    Restored,
+    /// The user has to connect again, i.e. approve the dApp or scan new pairing uri
+    NeedsUserAction(EthereumError),
+    /// Stored wallet is not available in current context
+    WalletMissing(EthereumError),
+    /// Stored WalletConnect session expired at given unix timestamp
+    SessionExpired(u64),
+    /// Stored state could not be read and was removed
+    StorageCorrupted(EthereumError),
+}
+
+impl RestoreOutcome {
+    pub fn is_restored(&self) -> bool {
+        matches!(self, Self::Restored)
+    }
 }
 
 impl From<EthereumError> for ProviderError {
@@ -227,8 +444,13 @@ pub(crate) enum WebProvider {
 }
 
 impl WebProvider {
+    /// Checks if provider is set and its WalletConnect session, if any, was not closed
     fn is_some(&self) -> bool {
-        !matches!(self, Self::None)
+        match self {
+            Self::None => false,
+            Self::Injected(_) => true,
+            Self::WalletConnect(provider) => !provider.is_closed(),
+        }
     }
 }
 
@@ -247,32 +469,152 @@ impl PartialEq for WebProvider {
 #[derive(Clone, Serialize, Deserialize)]
 pub struct EthereumState {
     pub chain_id: Option<u64>,
-    pub wc_state: Option<WalletConnectState>,
+    /// Topic of WalletConnect session, resumed from sessions stored by `SessionStore`
+    #[serde(default)]
+    pub wc_session: Option<String>,
+    #[serde(default)]
+    pub active_account: Option<Address>,
+    #[serde(default)]
+    pub wallet: Option<explorer::WalletDescription>,
+    /// EIP-6963 rdns of connected browser extension
+    #[serde(default)]
+    pub injected_rdns: Option<String>,
+    /// Unix timestamp of WalletConnect session's expiry
+    #[serde(default)]
+    pub session_expiry: Option<u64>,
+}
+
+/// Reason of broken connection
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum BrokenReason {
+    #[error("Connection to WalletConnect relay was lost")]
+    RelayConnectionLost,
+
+    #[error("Injected provider lost connection to the chain")]
+    ProviderDisconnected,
 }
 
 /// Ethereum's connection event
 #[derive(Debug, Clone, PartialEq)]
 pub enum Event {
-    ConnectionWaiting(String),
+    ConnectionWaiting(PairingInfo),
     Connected,
     Disconnected,
-    Broken,
+    /// Connection is broken, reconnection is attempted according to `ReconnectPolicy`
+    Broken(BrokenReason),
+    /// Given attempt to reconnect broken connection is about to be made
+    Reconnecting(u32),
+    /// Broken connection was reestablished, keeping the session
+    Reconnected,
+    /// Connection moved to given state, see [`Ethereum::connection_state`]
+    ConnectionStateChanged(ConnectionState),
+    /// WalletConnect session expires soon, at given unix timestamp, and the wallet refused to
+    /// extend it
+    SessionExpiring(u64),
+    /// WalletConnect session was extended up to given unix timestamp
+    SessionExtended(u64),
+    SessionExpired,
     ChainIdChanged(Option<u64>),
     AccountsChanged(Option<Vec<Address>>),
+    ActiveAccountChanged(Option<Address>),
+    BalancesChanged(Balances),
+    NewBlock(BlockHeader),
+    /// Paired mobile wallet should be opened with given link to approve pending request
+    OpenWallet(String),
+    /// Wallet was asked for user's approval of given request
+    PromptOpened(PromptRequest),
+    /// Wallet answered given request
+    PromptClosed(PromptRequest),
 }
 
 impl Event {
+    /// Checks if event confirms connection whose state should be stored
     fn is_connection_established(&self) -> bool {
-        !matches!(
+        matches!(
+            self,
+            Self::Connected
+                | Self::Broken(_)
+                | Self::Reconnected
+                | Self::ChainIdChanged(Some(_))
+                | Self::AccountsChanged(Some(_))
+                | Self::ActiveAccountChanged(Some(_))
+                | Self::SessionExtended(_)
+        )
+    }
+
+    /// Checks if event ends connection whose stored state should be removed
+    fn is_connection_lost(&self) -> bool {
+        matches!(
             self,
             Self::ConnectionWaiting(_)
                 | Self::Disconnected
+                | Self::SessionExpired
                 | Self::ChainIdChanged(None)
                 | Self::AccountsChanged(None)
+                | Self::ActiveAccountChanged(None)
         )
     }
 }
 
+/// Kind of `Event` regardless of its data, used for filtering event streams
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    ConnectionWaiting,
+    Connected,
+    Disconnected,
+    Broken,
+    Reconnecting,
+    Reconnected,
+    ConnectionStateChanged,
+    SessionExpiring,
+    SessionExtended,
+    SessionExpired,
+    ChainIdChanged,
+    AccountsChanged,
+    ActiveAccountChanged,
+    BalancesChanged,
+    NewBlock,
+    OpenWallet,
+    PromptOpened,
+    PromptClosed,
+}
+
+impl Event {
+    pub fn kind(&self) -> EventKind {
+        match self {
+            Self::ConnectionWaiting(_) => EventKind::ConnectionWaiting,
+            Self::Connected => EventKind::Connected,
+            Self::Disconnected => EventKind::Disconnected,
+            Self::Broken(_) => EventKind::Broken,
+            Self::Reconnecting(_) => EventKind::Reconnecting,
+            Self::Reconnected => EventKind::Reconnected,
+            Self::ConnectionStateChanged(_) => EventKind::ConnectionStateChanged,
+            Self::SessionExpiring(_) => EventKind::SessionExpiring,
+            Self::SessionExtended(_) => EventKind::SessionExtended,
+            Self::SessionExpired => EventKind::SessionExpired,
+            Self::ChainIdChanged(_) => EventKind::ChainIdChanged,
+            Self::AccountsChanged(_) => EventKind::AccountsChanged,
+            Self::ActiveAccountChanged(_) => EventKind::ActiveAccountChanged,
+            Self::BalancesChanged(_) => EventKind::BalancesChanged,
+            Self::NewBlock(_) => EventKind::NewBlock,
+            Self::OpenWallet(_) => EventKind::OpenWallet,
+            Self::PromptOpened(_) => EventKind::PromptOpened,
+            Self::PromptClosed(_) => EventKind::PromptClosed,
+        }
+    }
+
+    /// Connection state the event moves to
+    fn connection_state(&self) -> Option<ConnectionState> {
+        match self {
+            Self::ConnectionWaiting(_) => Some(ConnectionState::AwaitingPairing),
+            Self::Connected | Self::Reconnected => Some(ConnectionState::Connected),
+            Self::Disconnected | Self::SessionExpired => Some(ConnectionState::Idle),
+            Self::Reconnecting(_) => Some(ConnectionState::Reconnecting),
+            _ => None,
+        }
+    }
+}
+
 impl From<WalletConnectEvent> for Event {
     fn from(value: WalletConnectEvent) -> Self {
         match value {
@@ -280,7 +622,7 @@ impl From<WalletConnectEvent> for Event {
             WalletConnectEvent::Connected => Self::Connected,
             WalletConnectEvent::AccountsChanged(acc) => Self::AccountsChanged(acc),
             WalletConnectEvent::ChainIdChanged(id) => Self::ChainIdChanged(Some(id)),
-            WalletConnectEvent::Broken => Self::Broken,
+            WalletConnectEvent::Broken => Self::Broken(BrokenReason::RelayConnectionLost),
         }
     }
 }
@@ -290,14 +632,33 @@ impl From<WalletConnectEvent> for Event {
 pub struct Ethereum {
     pub metadata: Metadata,
     pub wc_project_id: Option<String>,
+    pub wc_relay_url: Option<Url>,
+    pub wc_verify_url: Option<Url>,
+    pub wc_proposal: SessionProposal,
+    pub explorer_url: Option<Url>,
+    pub explorer_cache_ttl: Option<u64>,
+    pub reconnect_policy: ReconnectPolicy,
     pub rpc_node: Option<String>,
     pub http_provider: Option<Http>,
+    pub ens: Option<EnsResolver>,
+    pub chains: Arc<ChainRegistry>,
 
     accounts: Option<Vec<Address>>,
     chain_id: Option<u64>,
+    active_account: Arc<RwLock<Option<Address>>>,
+    balance_watcher: BalanceWatcher,
+    block_watcher: BlockWatcher,
+    navigate_to_wallet: bool,
+    expiry_watch: LoopGuard,
+    pairing_refresh: LoopGuard,
+    reconnection: Reconnection,
+    connection: ConnectionMachine,
+    prompts: PromptQueue,
 
     sender: Sender<Event>,
     receiver: Arc<Mutex<Receiver<Event>>>,
+    /// Notified whenever wallet updates chain or accounts of the session
+    session_updates: Arc<watch::Sender<()>>,
 
     wallet: WebProvider,
 }
@@ -306,45 +667,82 @@ impl PartialEq for Ethereum {
     fn eq(&self, other: &Self) -> bool {
         self.metadata == other.metadata
             && self.wc_project_id == other.wc_project_id
+            && self.wc_proposal == other.wc_proposal
             && self.rpc_node == other.rpc_node
             && self.accounts == other.accounts
             && self.chain_id == other.chain_id
+            && self.active_account() == other.active_account()
             && self.wallet == other.wallet
     }
 }
 
 impl Debug for Ethereum {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
-        write!(f, "Ethereum with accounts: {:?}, chain_id: {:?} ", self.accounts, self.chain_id)
+        write!(
+            f,
+            "Ethereum with accounts: {:?}, active account: {:?}, chain_id: {:?} ",
+            self.accounts,
+            self.active_account(),
+            self.chain_id
+        )
     }
 }
 
 impl Ethereum {
     /// Ethereum constructor
-    fn new(
-        chain_id: u64,
-        name: String,
-        description: String,
-        url: Url,
-        wc_project_id: Option<String>,
-        icons: Vec<String>,
-        rpc_node: Option<String>,
-    ) -> Self {
+    fn new(builder: &EthereumBuilder) -> Self {
         let (sender, receiver) = channel::<Event>(10);
 
+        let rpc_node = builder.rpc_node.clone();
         let http_provider = match rpc_node {
             Some(ref url) => Some(Http::from_str(&url).unwrap()),
             None => None,
         };
+
+        // Without dedicated mainnet node we can only use regular one if it points to mainnet
+        let mainnet_rpc_node = match (&builder.mainnet_rpc_node, builder.chain_id) {
+            (Some(node), _) => Some(node),
+            (None, 1) => rpc_node.as_ref(),
+            _ => None,
+        };
+        let ens = mainnet_rpc_node
+            .and_then(|node| EnsResolver::new(node, builder.ipfs_gateway.as_deref()).ok());
+
         Ethereum {
-            metadata: Metadata::from(&name, &description, url, icons),
-            wc_project_id,
+            metadata: Metadata::from(
+                &builder.name,
+                &builder.description,
+                builder.url.clone(),
+                builder.icons.clone(),
+            ),
+            wc_project_id: builder.wc_project_id.clone(),
+            wc_relay_url: builder.wc_relay_url.clone(),
+            wc_verify_url: builder.wc_verify_url.clone(),
+            wc_proposal: builder.wc_proposal.clone(),
+            explorer_url: builder.explorer_url.clone(),
+            explorer_cache_ttl: builder.explorer_cache_ttl,
+            reconnect_policy: builder.reconnect_policy,
             rpc_node,
             http_provider,
+            ens,
+            chains: Arc::new(builder.chains.clone()),
             accounts: None,
-            chain_id: Some(chain_id),
+            chain_id: Some(builder.chain_id),
+            active_account: Arc::new(RwLock::new(None)),
+            balance_watcher: BalanceWatcher::new(
+                builder.watch_balances,
+                builder.watched_tokens.clone(),
+            ),
+            block_watcher: BlockWatcher::new(builder.watch_blocks),
+            navigate_to_wallet: builder.navigate_to_wallet,
+            expiry_watch: LoopGuard::default(),
+            pairing_refresh: LoopGuard::default(),
+            reconnection: Reconnection::default(),
+            connection: ConnectionMachine::default(),
+            prompts: PromptQueue::default(),
             sender,
             receiver: Arc::new(Mutex::new(receiver)),
+            session_updates: Arc::new(watch::channel(()).0),
             wallet: WebProvider::None,
         }
     }
@@ -366,6 +764,7 @@ impl Ethereum {
     pub fn connected_wallet_type(&self) -> Option<WalletType> {
         match &self.wallet {
             WebProvider::None => None,
+            WebProvider::WalletConnect(provider) if provider.is_closed() => None,
             WebProvider::Injected(_) => Some(WalletType::Injected),
             WebProvider::WalletConnect(_) => Some(WalletType::WalletConnect),
         }
@@ -391,78 +790,751 @@ impl Ethereum {
         Eip1193::is_available()
     }
 
+    /// Returns browser extensions detected in current context
+    pub fn injected_wallets(&self) -> InjectedWallets {
+        InjectedWallets {
+            announced: Eip1193::announced(),
+            flags: Eip1193::provider_flags(),
+            available: Eip1193::is_available(),
+        }
+    }
+
+    /// Returns wallets to pick from, merging explorer wallets with detected browser extensions.
+    /// Without WalletConnect configured, bundled wallets are listed with their install links.
+    pub async fn wallet_options(&self) -> Vec<WalletOption> {
+        let injected = self.injected_wallets();
+        let platform = Platform::detect();
+        let recent = RecentWallets::load().wallets;
+        let Ok(explorer) = self.explorer() else {
+            let wallets = ExplorerResponse::bundled().parse_wallets("");
+            let mut options = wallet_options(&wallets, &injected, platform, false);
+            prefer_recent(&mut options, &recent);
+            return options;
+        };
+
+        let wallets = explorer.fetch_wallets(&WalletQuery::default()).await.wallets;
+        let mut options = wallet_options(&wallets, &injected, platform, true);
+        for option in options.iter_mut().filter(|o| o.icon.is_none()) {
+            option.icon =
+                option.wallet.as_ref().map(|w| explorer.logo_url(w, ImageSize::Small).to_string());
+        }
+        prefer_recent(&mut options, &recent);
+        options
+    }
+
+    /// Returns recently used wallets, the last used first. They are remembered after
+    /// disconnecting.
+    pub fn recent_wallets(&self) -> Vec<RecentWallet> {
+        RecentWallets::load().wallets
+    }
+
+    /// Returns the last used wallet
+    pub fn last_used_wallet(&self) -> Option<RecentWallet> {
+        RecentWallets::load().last().cloned()
+    }
+
+    /// Forgets recently used wallet
+    pub fn forget_recent_wallet(&self, wallet: &RecentWallet) {
+        let mut recent = RecentWallets::load();
+        if recent.remove(wallet) {
+            recent.save();
+        }
+    }
+
+    fn record_recent_wallet(&self) {
+        let wallet = match &self.wallet {
+            WebProvider::None => return,
+            WebProvider::Injected(provider) => {
+                let rdns = provider.rdns().map(String::from);
+                let name = rdns
+                    .as_ref()
+                    .and_then(|rdns| Eip1193::announced().into_iter().find(|i| &i.rdns == rdns))
+                    .map(|info| info.name)
+                    .unwrap_or("Browser Wallet".to_string());
+                RecentWallet {
+                    wallet_type: WalletType::Injected,
+                    id: rdns,
+                    name,
+                    wallet: None,
+                    last_used: now(),
+                }
+            }
+            WebProvider::WalletConnect(provider) => {
+                let wallet = provider.wallet();
+                let peer = provider.peer_metadata();
+                let name = wallet
+                    .as_ref()
+                    .map(|w| w.name.clone())
+                    .or_else(|| peer.as_ref().map(|m| m.name.clone()))
+                    .unwrap_or("WalletConnect".to_string());
+                RecentWallet {
+                    wallet_type: WalletType::WalletConnect,
+                    id: recent_wc_id(&wallet, &peer),
+                    name,
+                    wallet,
+                    last_used: now(),
+                }
+            }
+        };
+
+        let mut recent = RecentWallets::load();
+        recent.record(wallet);
+        recent.save();
+    }
+
     /// Checks if WalletConnect connection is available in current context (configuration)
     pub fn walletconnect_available(&self) -> bool {
         self.wc_project_id.is_some()
     }
 
-    /// Fetching available wallets from WalletConnect explorer
+    /// Returns WalletConnect explorer client of configured explorer
+    pub fn explorer(&self) -> Result<ExplorerClient, EthereumError> {
+        let project_id = self.wc_project_id.as_deref().ok_or(EthereumError::Unavailable)?;
+        let client = match &self.explorer_url {
+            Some(url) => ExplorerClient::with_base_url(project_id, url.clone()),
+            None => ExplorerClient::new(project_id),
+        };
+        Ok(client.with_cache_ttl(self.explorer_cache_ttl))
+    }
+
+    /// Fetching available wallets from WalletConnect explorer, or its cache. Bundled list of
+    /// popular wallets is returned when explorer is unreachable.
     pub async fn fetch_available_wallets(
         &self,
     ) -> Result<Vec<explorer::WalletDescription>, EthereumError> {
-        match &self.wc_project_id {
+        Ok(self.query_wallets(&WalletQuery::default()).await?.wallets)
+    }
+
+    /// Fetching page of wallets matching the query from WalletConnect explorer. Check page's
+    /// `source` to tell if wallets came from bundled list.
+    pub async fn query_wallets(&self, query: &WalletQuery) -> Result<WalletPage, EthereumError> {
+        Ok(self.explorer()?.fetch_wallets(query).await)
+    }
+
+    /// Returns chain id wallet is currently connected to, or the default one
+    pub fn current_chain_id(&self) -> Option<u64> {
+        match &self.wallet {
+            WebProvider::WalletConnect(provider) => Some(provider.chain_id()),
+            _ => self.chain_id,
+        }
+    }
+
+    /// Returns namespaces granted by WalletConnect wallet after session approval
+    pub fn granted_namespaces(&self) -> Option<BTreeMap<String, GrantedNamespace>> {
+        match &self.wallet {
+            WebProvider::WalletConnect(provider) => provider.granted_namespaces(),
+            _ => None,
+        }
+    }
+
+    /// Returns stored WalletConnect sessions that can be resumed
+    pub fn wc_sessions(&self) -> Vec<StoredSession> {
+        SessionStore::load().sessions
+    }
+
+    /// Returns stored WalletConnect pairings that can be reused for new sessions
+    pub fn wc_pairings(&self) -> Vec<StoredPairing> {
+        SessionStore::load().pairings
+    }
+
+    /// Returns description of the chain wallet is currently connected to
+    pub fn current_chain(&self) -> Option<&Chain> {
+        self.current_chain_id().and_then(|id| self.chains.get(id))
+    }
+
+    /// Fetches ENS name and avatar of given address. Results are cached per address.
+    pub async fn lookup_ens(&self, address: Address) -> Result<EnsProfile, EthereumError> {
+        match &self.ens {
+            Some(ens) => Ok(ens.profile(address).await?),
+            None => Err(EthereumError::Unavailable),
+        }
+    }
+
+    /// Resolves ENS name into an address
+    pub async fn resolve_ens_name(&self, name: &str) -> Result<Option<Address>, EthereumError> {
+        match &self.ens {
+            Some(ens) => Ok(ens.resolve_name(name).await?),
             None => Err(EthereumError::Unavailable),
-            Some(p_id) => {
-                let client = reqwest::Client::new();
-                let resp: explorer::ExplorerResponse = client
-                    .get(format!(
-                        "https://explorer-api.walletconnect.com/v3/wallets?projectId={p_id}",
-                    ))
-                    .send()
-                    .await?
-                    .json()
-                    .await?;
-                Ok(resp.parse_wallets(p_id))
+        }
+    }
+
+    /// Returns latest known balances of the active account
+    pub fn balances(&self) -> Balances {
+        self.balance_watcher.current()
+    }
+
+    /// Returns stream of balance updates of the active account, starting with the latest known
+    /// balances
+    pub fn subscribe_balances(&self) -> impl Stream<Item = Balances> {
+        self.balance_watcher.subscribe()
+    }
+
+    /// Returns ERC-20 tokens which balances are tracked
+    pub fn watched_tokens(&self) -> Vec<Address> {
+        self.balance_watcher.tokens()
+    }
+
+    /// Starts tracking balance of ERC-20 token for the active account
+    pub async fn watch_token(&self, token: Address) -> Result<Balances, EthereumError> {
+        self.balance_watcher.add_token(token);
+        if self.has_provider() {
+            self.start_block_watcher();
+        }
+        self.refresh_balances().await
+    }
+
+    /// Stops tracking balance of ERC-20 token
+    pub async fn unwatch_token(&self, token: Address) -> Result<Balances, EthereumError> {
+        self.balance_watcher.remove_token(&token);
+        self.refresh_balances().await
+    }
+
+    /// Fetches balances of the active account and notifies about changes
+    pub async fn refresh_balances(&self) -> Result<Balances, EthereumError> {
+        let account = self.active_account();
+        let chain_id = self.request_chain_id().await.ok().map(|c| c.low_u64());
+        let mut balances = Balances { account, chain_id, ..Default::default() };
+
+        if let Some(account) = account {
+            let (native, tokens) =
+                fetch_balances(self, account, &self.balance_watcher.tokens()).await?;
+            balances.native = Some(native);
+            balances.tokens = tokens;
+        }
+
+        if self.balance_watcher.update(balances.clone()) {
+            _ = self.sender.send(Event::BalancesChanged(balances.clone())).await;
+        }
+        Ok(balances)
+    }
+
+    fn spawn_balance_refresh(&self) {
+        let eth = self.clone();
+        spawn_local(async move {
+            if let Err(err) = eth.refresh_balances().await {
+                error!("Balances not refreshed {err:?}");
+            }
+        });
+    }
+
+    /// Returns the latest block observed by block watcher
+    pub fn latest_block(&self) -> Option<BlockHeader> {
+        self.block_watcher.latest()
+    }
+
+    /// Returns stream of new blocks observed by block watcher. Watcher is started if needed.
+    pub fn subscribe_blocks(&self) -> impl Stream<Item = BlockHeader> {
+        self.block_watcher.enable();
+        if self.has_provider() || self.http_provider.is_some() {
+            self.start_block_watcher();
+        }
+        self.block_watcher.subscribe()
+    }
+
+    /// Starts polling for new blocks. Polling interval adapts to the chain's block time and
+    /// polling is paused while the page is hidden. Every new block is announced with
+    /// `Event::NewBlock`.
+    pub fn start_block_watcher(&self) {
+        if !self.block_watcher.start() {
+            return;
+        }
+
+        let eth = self.clone();
+        spawn_local(async move {
+            let mut interval = PollInterval::default();
+            while eth.block_watcher.is_running() {
+                page_visible().await;
+
+                let last = eth.block_watcher.latest().map(|b| b.number);
+                if last.is_none() {
+                    interval.reset();
+                }
+                match fetch_new_block(&eth, last).await {
+                    Ok(Some(header)) => {
+                        interval.on_new_block(header.timestamp.low_u64());
+                        eth.block_watcher.update(header.clone());
+                        if let Err(err) = eth.sender.try_send(Event::NewBlock(header)) {
+                            warn!("New block not reported {err:?}");
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(err) => error!("New block not fetched {err:?}"),
+                }
+                TimeoutFuture::new(interval.current()).await;
             }
+        });
+    }
+
+    /// Stops polling for new blocks
+    pub fn stop_block_watcher(&self) {
+        self.block_watcher.stop();
+    }
+
+    /// Returns unix timestamp of current WalletConnect session's expiry
+    pub fn session_expiry(&self) -> Option<u64> {
+        match &self.wallet {
+            WebProvider::WalletConnect(provider) => provider.session_expiry(),
+            _ => None,
         }
     }
 
+    /// Keeps WalletConnect session alive by pinging the wallet and extending the session close to
+    /// its expiry. Reports the session expiring if the wallet refuses to extend it, and once it
+    /// expires.
+    fn start_expiry_watch(&self) {
+        let WebProvider::WalletConnect(provider) = &self.wallet else {
+            return;
+        };
+
+        let provider = provider.clone();
+        let sender = self.sender.clone();
+        let expiry_watch = self.expiry_watch.clone();
+        let generation = expiry_watch.start();
+        spawn_local(async move {
+            let mut warned = false;
+            loop {
+                let expiry = provider.session_expiry();
+                match ExpiryStatus::of(expiry, now()) {
+                    ExpiryStatus::Valid => {
+                        if let Err(err) = wallet_answer(provider.ping()).await {
+                            debug!("Session ping failed {err:?}");
+                        }
+                    }
+                    ExpiryStatus::Expiring => {
+                        let extended = wallet_answer(provider.extend()).await;
+                        if !expiry_watch.is_current(generation) {
+                            break;
+                        }
+                        match extended {
+                            Ok(()) => {
+                                warned = false;
+                                let expiry = provider.session_expiry().unwrap_or_default();
+                                _ = sender.send(Event::SessionExtended(expiry)).await;
+                            }
+                            Err(err) if !warned => {
+                                debug!("Session not extended {err:?}");
+                                warned = true;
+                                let expiry = expiry.unwrap_or_default();
+                                _ = sender.send(Event::SessionExpiring(expiry)).await;
+                            }
+                            Err(err) => debug!("Session not extended {err:?}"),
+                        }
+                    }
+                    ExpiryStatus::Expired => {
+                        expiry_watch.stop();
+                        _ = sender.send(Event::SessionExpired).await;
+                        break;
+                    }
+                }
+
+                let Some(wait) = ExpiryStatus::until_change(provider.session_expiry(), now())
+                else {
+                    break;
+                };
+                TimeoutFuture::new((wait.min(MAX_CHECK_INTERVAL_SECS) * 1000) as u32).await;
+                if !expiry_watch.is_current(generation) {
+                    break;
+                }
+            }
+        });
+    }
+
+    /// Proposes new pairing every time the previous one expires before the wallet approves it
+    fn start_pairing_refresh(&self, pairing: PairingInfo) {
+        let WebProvider::WalletConnect(provider) = &self.wallet else {
+            return;
+        };
+
+        let provider = provider.clone();
+        let sender = self.sender.clone();
+        let guard = self.pairing_refresh.clone();
+        let generation = guard.start();
+        spawn_local(async move {
+            let mut pairing = pairing;
+            while let Some(expiry) = pairing.expiry {
+                let wait = expiry.saturating_sub(now()).saturating_mul(1_000);
+                TimeoutFuture::new(wait.min(u32::MAX as u64) as u32).await;
+                if !guard.is_current(generation) || provider.session_topic().is_some() {
+                    break;
+                }
+
+                let refreshed = match provider.propose().await {
+                    Ok(uri) => PairingInfo::parse(&uri, now()),
+                    Err(err) => Err(err.into()),
+                };
+                match refreshed {
+                    Ok(refreshed) => {
+                        debug!("Pairing expired, proposing new one {:?}", refreshed.topic);
+                        pairing = refreshed;
+                        _ = sender.send(Event::ConnectionWaiting(pairing.clone())).await;
+                    }
+                    Err(err) => {
+                        error!("Pairing not refreshed {err:?}");
+                        break;
+                    }
+                }
+            }
+        });
+    }
+
+    /// Block watcher is needed if it was requested directly or by balance watcher
+    fn needs_block_watcher(&self) -> bool {
+        self.block_watcher.is_enabled() || self.balance_watcher.is_enabled()
+    }
+
     /// Performing connection to selected wallet
     pub async fn connect(&mut self, wallet: WalletType) -> Result<(), EthereumError> {
-        if self.wallet != WebProvider::None {
+        if self.wallet.is_some() {
             return Err(EthereumError::AlreadyConnected);
         }
 
         match wallet {
-            WalletType::Injected => self.connect_injected().await,
-            WalletType::WalletConnect => self.connect_wc(None).await,
+            WalletType::Injected => self.connect_injected(None, true).await,
+            WalletType::WalletConnect => self.connect_wc(None, None).await.map(|_| ()),
+        }
+    }
+
+    /// Performing connection to wallet picked from `wallet_options`. Returns link opening the
+    /// wallet, if it has to be opened to approve the connection.
+    pub async fn connect_option(
+        &mut self,
+        option: &WalletOption,
+    ) -> Result<Option<String>, EthereumError> {
+        if self.wallet.is_some() {
+            return Err(EthereumError::AlreadyConnected);
+        }
+
+        match &option.action {
+            WalletAction::Injected(rdns) => {
+                self.connect_injected(rdns.as_deref(), true).await.map(|_| None)
+            }
+            WalletAction::WalletConnect => match &option.wallet {
+                Some(wallet) if wallet.supports(Platform::detect()) => {
+                    self.connect_with_wallet(wallet).await.map(Some)
+                }
+                wallet => self.connect_wc(None, wallet.clone()).await.map(|_| None),
+            },
+            WalletAction::Install(_) => Err(EthereumError::UnsupportedWallet(option.name.clone())),
+        }
+    }
+
+    /// Starts WalletConnect session with given wallet. Returns link opening the wallet with
+    /// pairing request, suitable for the platform dApp is running on.
+    pub async fn connect_with_wallet(
+        &mut self,
+        wallet: &explorer::WalletDescription,
+    ) -> Result<String, EthereumError> {
+        if self.wallet.is_some() {
+            return Err(EthereumError::AlreadyConnected);
+        }
+
+        let platform = explorer::Platform::detect();
+        if !wallet.supports(platform) {
+            return Err(EthereumError::UnsupportedWallet(wallet.name.clone()));
+        }
+
+        let uri = self
+            .connect_wc(None, Some(wallet.clone()))
+            .await?
+            .ok_or(EthereumError::MissingPairingUri)?;
+        wallet
+            .pairing_link(&uri, platform)
+            .ok_or_else(|| EthereumError::UnsupportedWallet(wallet.name.clone()))
+    }
+
+    /// Reconnects to recently used wallet, resuming its stored WalletConnect session or pairing
+    /// when there is one. Returns link opening the wallet, if it has to be opened to approve
+    /// the connection.
+    pub async fn reconnect_recent(
+        &mut self,
+        recent: &RecentWallet,
+    ) -> Result<Option<String>, EthereumError> {
+        if self.wallet.is_some() {
+            return Err(EthereumError::AlreadyConnected);
+        }
+
+        if recent.wallet_type == WalletType::Injected {
+            return self.connect_injected(recent.id.as_deref(), true).await.map(|_| None);
+        }
+
+        let store = SessionStore::load();
+        let now = now();
+        let is_recent = |wallet: &Option<explorer::WalletDescription>, peer: &Option<Metadata>| {
+            recent.id.is_some() && recent_wc_id(wallet, peer) == recent.id
+        };
+        let session =
+            store.sessions.iter().find(|s| is_recent(&s.wallet, &s.peer) && !s.is_expired(now));
+        if let Some(session) = session {
+            match self.resume_session(&session.topic).await {
+                // Session is forgotten already, new one is proposed below
+                Err(EthereumError::UnknownSession(_)) => {}
+                result => return result.map(|_| None),
+            }
+        }
+        let pairing =
+            store.pairings.iter().find(|p| is_recent(&p.wallet, &p.peer) && !p.is_expired(now));
+        if let Some(pairing) = pairing {
+            self.connect_with_pairing(&pairing.topic).await?;
+            let platform = explorer::Platform::detect();
+            return Ok(recent.wallet.as_ref().and_then(|w| w.open_link(platform)));
+        }
+
+        match &recent.wallet {
+            Some(wallet) if wallet.supports(explorer::Platform::detect()) => {
+                self.connect_with_wallet(wallet).await.map(Some)
+            }
+            wallet => self.connect_wc(None, wallet.clone()).await.map(|_| None),
+        }
+    }
+
+    /// Resumes stored WalletConnect session. Expired or unreadable session is forgotten without
+    /// connecting, failing with `EthereumError::UnknownSession`, and the wallet has to approve
+    /// new pairing.
+    pub async fn resume_session(&mut self, topic: &str) -> Result<(), EthereumError> {
+        if self.wallet.is_some() {
+            return Err(EthereumError::AlreadyConnected);
+        }
+
+        let session = SessionStore::load()
+            .session(topic)
+            .cloned()
+            .ok_or_else(|| EthereumError::UnknownSession(topic.to_string()))?;
+
+        // Nothing is announced for sessions which cannot be resumed, they are forgotten first
+        let state = match session.state() {
+            Ok(state) if !session.is_expired(now()) => Some(state),
+            Ok(_) => None,
+            Err(err) => {
+                warn!("Stored session {topic} is unreadable: {err}");
+                None
+            }
+        };
+        let Some(state) = state else {
+            self.delete_session(topic).await;
+            return Err(EthereumError::UnknownSession(topic.to_string()));
+        };
+        self.store_active_account(session.active_account);
+        self.chain_id = Some(session.chain_id);
+        // Stored state holds the session key, so the client subscribes to the session topic
+        // instead of proposing new pairing
+        self.connect_wc(Some(state), session.wallet).await.map(|_| ())
+    }
+
+    /// Proposes new WalletConnect session through stored pairing, so the wallet receives it
+    /// without scanning new pairing uri
+    pub async fn connect_with_pairing(&mut self, topic: &str) -> Result<(), EthereumError> {
+        if self.wallet.is_some() {
+            return Err(EthereumError::AlreadyConnected);
+        }
+
+        let pairing = SessionStore::load()
+            .pairing(topic)
+            .cloned()
+            .ok_or_else(|| EthereumError::UnknownSession(topic.to_string()))?;
+
+        let proposal = self.wc_proposal.state(self.chain_id.unwrap_or(1), &self.metadata)?;
+        let state = pairing.state(proposal)?;
+        self.connect_wc(Some(state), pairing.wallet).await.map(|_| ())
+    }
+
+    /// Forgets stored WalletConnect session, disconnecting it if it is the current one
+    pub async fn delete_session(&mut self, topic: &str) {
+        if let WebProvider::WalletConnect(provider) = &self.wallet {
+            if provider.session_topic().as_deref() == Some(topic) {
+                self.disconnect().await;
+            }
+        }
+
+        let mut store = SessionStore::load();
+        if store.remove_session(topic).is_some() {
+            store.save();
+        }
+    }
+
+    /// Forgets stored WalletConnect pairing with all its sessions, disconnecting current session
+    /// if it was established through that pairing
+    pub async fn delete_pairing(&mut self, topic: &str) {
+        if let WebProvider::WalletConnect(provider) = &self.wallet {
+            if provider.pairing_topic().as_deref() == Some(topic) {
+                self.disconnect().await;
+            }
+        }
+
+        let mut store = SessionStore::load();
+        if store.remove_pairing(topic).is_some() {
+            store.save();
         }
     }
 
     /// Disconnects from wallet
     pub async fn disconnect(&mut self) {
+        self.set_connection_state(ConnectionState::Disconnecting).await;
         if let WebProvider::WalletConnect(wc) = &self.wallet {
             wc.disconnect().await;
+            forget_wc_session(wc);
         }
+        self.expiry_watch.stop();
+        self.pairing_refresh.stop();
+        self.reconnection.cancel();
 
         self.wallet = WebProvider::None;
         self.accounts = None;
 
         _ = self.sender.send(Event::Disconnected).await;
+        if let Some(changed) = self.sync_active_account(None) {
+            _ = self.sender.send(Event::ActiveAccountChanged(changed)).await;
+        }
+    }
+
+    /// Returns current state of the connection
+    pub fn connection_state(&self) -> ConnectionState {
+        self.connection.state()
+    }
+
+    /// Moves connection to given state, announcing the change. Returns `false` if the state
+    /// was not changed, as it would be illegal transition or the same state.
+    async fn set_connection_state(&self, state: ConnectionState) -> bool {
+        match self.connection.transition(state) {
+            Transition::Changed(state) => {
+                _ = self.sender.send(Event::ConnectionStateChanged(state)).await;
+                true
+            }
+            Transition::Unchanged | Transition::Illegal => false,
+        }
+    }
+
+    /// Queues event without waiting for room in the channel. `next()` drains the channel itself,
+    /// so events it emits would wait for it forever once the channel is full.
+    fn queue_event(&self, event: Event) {
+        if let Err(err) = self.sender.try_send(event) {
+            warn!("Event not queued {err:?}");
+        }
+    }
+
+    /// Moves connection state according to the event. Returns `false` if the event should be
+    /// dropped, repeating current state or being impossible in it.
+    fn track_connection(&self, event: &Event) -> bool {
+        let Some(state) = event.connection_state() else {
+            return true;
+        };
+
+        match self.connection.transition(state) {
+            Transition::Changed(state) => {
+                self.queue_event(Event::ConnectionStateChanged(state));
+                true
+            }
+            Transition::Unchanged => !matches!(event, Event::Connected | Event::Disconnected),
+            Transition::Illegal => false,
+        }
+    }
+
+    /// Returns currently selected account of connected wallet
+    pub fn active_account(&self) -> Option<Address> {
+        self.active_account.read().map(|a| *a).unwrap_or_default()
+    }
+
+    /// Returns account used as a sender for transactions that do not specify one
+    pub fn default_sender(&self) -> Option<Address> {
+        self.active_account()
     }
 
-    async fn connect_injected(&mut self) -> Result<(), EthereumError> {
-        if !self.injected_available() {
+    /// Selects active account from the list of accounts provided by connected wallet
+    pub async fn set_active_account(&self, account: Address) -> Result<(), EthereumError> {
+        if !self.current_accounts().await?.contains(&account) {
+            return Err(EthereumError::UnknownAccount(account));
+        }
+
+        if self.active_account() != Some(account) {
+            self.store_active_account(Some(account));
+            _ = self.sender.send(Event::ActiveAccountChanged(Some(account))).await;
+        }
+        Ok(())
+    }
+
+    fn store_active_account(&self, account: Option<Address>) {
+        if let Ok(mut active) = self.active_account.write() {
+            *active = account;
+        }
+    }
+
+    /// Synchronizes active account with given accounts and notifies about current selection
+    async fn announce_active_account(&self, accounts: Option<&Vec<Address>>) {
+        self.sync_active_account(accounts);
+        _ = self.sender.send(Event::ActiveAccountChanged(self.active_account())).await;
+    }
+
+    /// Keeps active account in sync with accounts list, preserving current selection if it is
+    /// still available. Returns new active account if it has changed.
+    fn sync_active_account(&self, accounts: Option<&Vec<Address>>) -> Option<Option<Address>> {
+        let current = self.active_account();
+        let selected = match accounts {
+            Some(acc) if current.is_some_and(|a| acc.contains(&a)) => current,
+            Some(acc) => acc.first().copied(),
+            None => None,
+        };
+
+        if selected != current {
+            self.store_active_account(selected);
+            Some(selected)
+        } else {
+            None
+        }
+    }
+
+    /// Connects to injected wallet. Without prompting, connection succeeds only if the wallet
+    /// still exposes accounts to the dApp.
+    async fn connect_injected(
+        &mut self,
+        rdns: Option<&str>,
+        prompt: bool,
+    ) -> Result<(), EthereumError> {
+        self.set_connection_state(ConnectionState::Connecting).await;
+        let result = self.bind_injected(rdns, prompt).await;
+        if let Err(err) = &result {
+            self.set_connection_state(ConnectionState::Failed(err.to_string())).await;
+        }
+        result
+    }
+
+    async fn bind_injected(
+        &mut self,
+        rdns: Option<&str>,
+        prompt: bool,
+    ) -> Result<(), EthereumError> {
+        let injected = match rdns {
+            Some(rdns) => {
+                // Announcements are collected only after the first request
+                Eip1193::announced();
+                Eip1193::with_rdns(rdns)
+            }
+            None => Eip1193::new(),
+        };
+        if !injected.is_present() {
             return Err(EthereumError::Unavailable);
         }
 
-        let injected = Eip1193::new();
+        let method = if prompt { "eth_requestAccounts" } else { "eth_accounts" };
+        let accounts: Vec<Address> = injected.request(method, ()).await?;
+        if accounts.is_empty() {
+            return Err(EthereumError::Unauthorized);
+        }
 
         {
             let s = self.sender.clone();
-            _ = injected.clone().on(
+            _ = injected.on(
                 WalletEvent::Disconnect,
                 Box::new(move |_| {
                     let sender = s.clone();
                     spawn_local(async move {
-                        _ = sender.send(Event::Disconnected).await;
+                        let reason = BrokenReason::ProviderDisconnected;
+                        _ = sender.send(Event::Broken(reason)).await;
                     })
                 }),
             );
         }
         {
             let s = self.sender.clone();
-            _ = injected.clone().on(
+            _ = injected.on(
                 WalletEvent::ChainChanged,
                 Box::new(move |chain_id| {
                     let sender = s.clone();
@@ -478,7 +1550,7 @@ impl Ethereum {
         }
         {
             let s = self.sender.clone();
-            _ = injected.clone().on(
+            _ = injected.on(
                 WalletEvent::AccountsChanged,
                 Box::new(move |accounts| {
                     let sender = s.clone();
@@ -502,7 +1574,7 @@ impl Ethereum {
             );
         }
         self.wallet = WebProvider::Injected(injected);
-        self.accounts = Some(self.request_accounts().await?);
+        self.accounts = Some(accounts);
         self.chain_id = Some(self.request_chain_id().await?.low_u64());
 
         _ = self.sender.send(Event::Connected).await;
@@ -512,110 +1584,372 @@ impl Ethereum {
         if self.accounts.is_some() {
             _ = self.sender.send(Event::AccountsChanged(self.accounts.clone())).await;
         }
+        self.announce_active_account(self.accounts.as_ref()).await;
 
         Ok(())
     }
 
     /// Getting next available event from the event queue
     pub async fn next(&self) -> Result<Option<Event>, EthereumError> {
-        let event = match &self.wallet {
-            WebProvider::WalletConnect(provider) => {
-                let mut recvr = self.receiver.lock().await;
-                tokio::select! {
-                    e = recvr.recv() => Ok(e),
-                    e = provider.next() => Ok(e?.map(|e| e.into()))
+        let event = loop {
+            let event = match &self.wallet {
+                // Client of broken connection is replaced once reconnected, polling it meanwhile
+                // would only repeat its failure
+                WebProvider::WalletConnect(provider) if !self.reconnection.is_active() => {
+                    let mut recvr = self.receiver.lock().await;
+                    tokio::select! {
+                        e = recvr.recv() => Ok(e),
+                        e = provider.next() => Ok(e?.map(|e| e.into()))
+                    }
+                }
+                _ => Ok(self.receiver.lock().await.recv().await),
+            };
+
+            if let Ok(Some(Event::ChainIdChanged(_) | Event::AccountsChanged(_))) = &event {
+                self.session_updates.send_replace(());
+            }
+
+            match &event {
+                Ok(Some(e)) if !self.track_connection(e) => {
+                    debug!("Dropping event {e:?} in state {:?}", self.connection_state());
                 }
+                _ => break event,
             }
-            _ => Ok(self.receiver.lock().await.recv().await),
         };
 
         debug!("NEW EVENT {:?}", event);
         if let Ok(Some(e)) = &event {
             if e == &Event::Connected {
                 if let WebProvider::WalletConnect(provider) = &self.wallet {
-                    _ = self.sender.send(Event::ChainIdChanged(Some(provider.chain_id()))).await;
-                    _ = self.sender.send(Event::AccountsChanged(provider.accounts())).await;
+                    self.queue_event(Event::ChainIdChanged(Some(provider.chain_id())));
+                    self.queue_event(Event::AccountsChanged(provider.accounts()));
+                    self.sync_active_account(provider.accounts().as_ref());
+                    self.queue_event(Event::ActiveAccountChanged(self.active_account()));
                 }
             }
 
-            if !e.is_connection_established() {
+            match e {
+                Event::Broken(_) => self.start_reconnection(),
+                Event::Disconnected | Event::SessionExpired => self.reconnection.cancel(),
+                _ => {}
+            }
+
+            if let WebProvider::WalletConnect(provider) = &self.wallet {
+                match e {
+                    Event::Connected => {
+                        self.pairing_refresh.stop();
+                        self.start_expiry_watch();
+                    }
+                    Event::Disconnected | Event::SessionExpired => {
+                        self.pairing_refresh.stop();
+                        self.expiry_watch.stop();
+                        forget_wc_session(provider);
+                    }
+                    _ => {}
+                }
+            }
+
+            if let Event::AccountsChanged(accounts) = e {
+                if let Some(changed) = self.sync_active_account(accounts.as_ref()) {
+                    self.queue_event(Event::ActiveAccountChanged(changed));
+                }
+            }
+
+            if self.needs_block_watcher() {
+                match e {
+                    Event::Connected => self.start_block_watcher(),
+                    Event::Disconnected => self.block_watcher.stop(),
+                    Event::ChainIdChanged(_) => self.block_watcher.reset(),
+                    _ => {}
+                }
+            }
+
+            if self.balance_watcher.is_enabled() {
+                match e {
+                    Event::ChainIdChanged(_)
+                    | Event::ActiveAccountChanged(_)
+                    | Event::NewBlock(_) => self.spawn_balance_refresh(),
+                    _ => {}
+                }
+            }
+
+            if e.is_connection_lost() {
                 LocalStorage::delete(STATUS_KEY);
-            } else {
+            } else if e.is_connection_established() {
                 _ = LocalStorage::set(STATUS_KEY, self.collect_state());
+                if e == &Event::Connected {
+                    self.record_recent_wallet();
+                }
+            }
+
+            // Stored session follows session's settlement and updates sent by the wallet
+            if let (
+                Event::Connected
+                | Event::ChainIdChanged(_)
+                | Event::AccountsChanged(_)
+                | Event::SessionExtended(_),
+                WebProvider::WalletConnect(provider),
+            ) = (e, &self.wallet)
+            {
+                let mut store = SessionStore::load();
+                if store.record(provider, self.active_account(), now()) {
+                    store.save();
+                }
             }
         }
 
         event
     }
 
+    /// Returns stream of events, processing each one the same way `next()` does. Events are taken
+    /// from the queue shared with `next()`, so only one of them should be consumed. The stream
+    /// borrows `self`, following its wallet, accounts and chain, and ends when fetching events
+    /// fails. Dropping the stream stops fetching.
+    pub fn events(&self) -> impl Stream<Item = Event> + '_ {
+        stream::unfold(self, |eth| async move {
+            loop {
+                match eth.next().await {
+                    Ok(Some(event)) => return Some((event, eth)),
+                    Ok(None) => {}
+                    Err(err) => {
+                        error!("Event stream ended {err:?}");
+                        return None;
+                    }
+                }
+            }
+        })
+    }
+
+    /// Returns stream of events of given kinds. Events of other kinds are still processed, only
+    /// not yielded.
+    pub fn events_of(&self, kinds: &[EventKind]) -> impl Stream<Item = Event> + '_ {
+        let kinds = kinds.to_vec();
+        self.events().filter(move |e| future::ready(kinds.contains(&e.kind())))
+    }
+
     /// Signs typed data using connected wallet
     pub async fn sign_typed_data<T: Send + Sync + Serialize>(
         &self,
         data: T,
         from: &Address,
     ) -> Result<Signature, EthereumError> {
-        match &self.wallet {
-            WebProvider::None => Err(EthereumError::NotConnected),
-            WebProvider::Injected(provider) => Ok(provider.sign_typed_data(data, from).await?),
-            WebProvider::WalletConnect(provider) => {
-                Ok(provider.sign_typed_data(data, from).await?)
+        let sign = async {
+            match &self.wallet {
+                WebProvider::None => Err(EthereumError::NotConnected),
+                WebProvider::Injected(provider) => Ok(provider.sign_typed_data(data, from).await?),
+                WebProvider::WalletConnect(provider) => {
+                    Ok(provider.sign_typed_data(data, from).await?)
+                }
             }
-        }
+        };
+        self.prompted("eth_signTypedData_v4", sign).await
+    }
+
+    /// Returns number of wallet requests waiting for user's approval, including the current one
+    pub fn prompt_queue_len(&self) -> usize {
+        self.prompts.len()
+    }
+
+    /// Returns wallet request currently waiting for user's approval
+    pub fn current_prompt(&self) -> Option<PromptRequest> {
+        self.prompts.current()
+    }
+
+    /// Makes request prompting the user once the requests queued before are answered, so the
+    /// wallet is not asked again while a request is pending
+    async fn prompted<R>(
+        &self,
+        method: &str,
+        request: impl std::future::Future<Output = Result<R, EthereumError>>,
+    ) -> Result<R, EthereumError> {
+        let ticket = self.prompts.enqueue(method);
+        let turn = ticket.open().await;
+        // Requests made while events are not consumed must not hang on a full channel
+        self.queue_event(Event::PromptOpened(ticket.request().clone()));
+
+        let result = request.await;
+        drop(turn);
+        self.queue_event(Event::PromptClosed(ticket.request().clone()));
+        result
     }
 
     /// Performs network switch to other chain id
     pub async fn switch_network(&mut self, chain_id: u64) -> Result<(), EthereumError> {
+        self.set_connection_state(ConnectionState::Switching).await;
+        let result = self.switch_wallet_network(chain_id).await;
+        if self.connection_state() == ConnectionState::Switching {
+            self.set_connection_state(ConnectionState::Connected).await;
+        }
+        result
+    }
+
+    async fn switch_wallet_network(&mut self, chain_id: u64) -> Result<(), EthereumError> {
         match self.wallet {
-            WebProvider::WalletConnect(ref mut provider) => {
-                // We need to check if we've got any accounts under that id
-                if let Some(accounts) = provider.accounts_for_chain(chain_id) {
-                    if !accounts.is_empty() {
-                        self.accounts = Some(accounts.clone());
-                        self.chain_id = Some(chain_id);
-                        provider.set_chain_id(chain_id);
-                        _ = self.sender.send(Event::ChainIdChanged(Some(chain_id))).await;
-                        _ = self.sender.send(Event::AccountsChanged(Some(accounts))).await;
-                        return Ok(());
+            WebProvider::WalletConnect(ref provider) => {
+                let provider = provider.clone();
+                if !provider.approves_chain(chain_id) {
+                    return Err(EthereumError::ChainNotApproved(chain_id));
+                }
+                let has_accounts =
+                    provider.accounts_for_chain(chain_id).is_some_and(|a| !a.is_empty());
+                let updates = self.session_updates.subscribe();
+
+                if provider.supports_method("wallet_switchEthereumChain") {
+                    let params = [json!({ "chainId": format!("0x{chain_id:x}") })];
+                    let switch = async {
+                        Ok(provider.request::<_, ()>("wallet_switchEthereumChain", params).await?)
+                    };
+                    match self.prompted("wallet_switchEthereumChain", switch).await {
+                        Ok(()) => {}
+                        Err(err) if is_unrecognized_chain(&err) => {
+                            let chain = self
+                                .chains
+                                .get(chain_id)
+                                .ok_or(EthereumError::UnknownChain(chain_id))?;
+                            let add = async {
+                                Ok(provider
+                                    .request::<_, ()>(
+                                        "wallet_addEthereumChain",
+                                        [chain.add_chain_params()],
+                                    )
+                                    .await?)
+                            };
+                            self.prompted("wallet_addEthereumChain", add).await?;
+                        }
+                        Err(err) => return Err(err),
+                    }
+                } else if !has_accounts {
+                    return Err(EthereumError::Unavailable);
+                }
+
+                // Wallet grants accounts of the new chain with session namespaces update
+                let accounts = wait_for_chain_accounts(&provider, chain_id, updates)
+                    .await
+                    .ok_or(EthereumError::ChainNotApproved(chain_id))?;
+
+                if let WebProvider::WalletConnect(provider) = &mut self.wallet {
+                    provider.set_chain_id(chain_id);
+                }
+                self.accounts = Some(accounts.clone());
+                self.chain_id = Some(chain_id);
+                _ = self.sender.send(Event::ChainIdChanged(Some(chain_id))).await;
+                _ = self.sender.send(Event::AccountsChanged(Some(accounts))).await;
+                if let Some(changed) = self.sync_active_account(self.accounts.as_ref()) {
+                    _ = self.sender.send(Event::ActiveAccountChanged(changed)).await;
+                }
+                Ok(())
+            }
+            WebProvider::Injected(_) => {
+                let params = [json!({ "chainId": format!("0x{chain_id:x}") })];
+                match self.request::<_, ()>("wallet_switchEthereumChain", params).await {
+                    Ok(()) => {}
+                    Err(err) if is_unrecognized_chain(&err) => {
+                        let chain = self
+                            .chains
+                            .get(chain_id)
+                            .ok_or(EthereumError::UnknownChain(chain_id))?;
+                        // Adding chain makes wallet switch to it as well
+                        self.request::<_, ()>(
+                            "wallet_addEthereumChain",
+                            [chain.add_chain_params()],
+                        )
+                        .await?;
                     }
+                    Err(err) => return Err(err),
                 }
-                Err(EthereumError::Unavailable)
+                self.chain_id = Some(chain_id);
+                Ok(())
             }
-            _ => Err(EthereumError::Unavailable),
+            WebProvider::None => Err(EthereumError::NotConnected),
+        }
+    }
+
+    /// Connects WalletConnect provider, optionally paired with given wallet. Returns pairing uri
+    /// if the session has to be approved by the wallet.
+    async fn connect_wc(
+        &mut self,
+        state: Option<WalletConnectState>,
+        wallet: Option<explorer::WalletDescription>,
+    ) -> Result<Option<String>, EthereumError> {
+        self.set_connection_state(ConnectionState::Connecting).await;
+        let result = self.start_wc(state, wallet).await;
+        if let Err(err) = &result {
+            self.set_connection_state(ConnectionState::Failed(err.to_string())).await;
         }
+        result
     }
 
-    async fn connect_wc(&mut self, state: Option<WalletConnectState>) -> Result<(), EthereumError> {
+    async fn start_wc(
+        &mut self,
+        state: Option<WalletConnectState>,
+        wallet: Option<explorer::WalletDescription>,
+    ) -> Result<Option<String>, EthereumError> {
         if !self.walletconnect_available() {
             return Err(EthereumError::Unavailable);
         }
 
-        let wc = WalletConnect::connect(
-            self.wc_project_id.clone().unwrap().into(),
-            self.chain_id.unwrap_or(1),
-            self.metadata.clone(),
-            state.clone(),
-        )?;
-
-        let url = wc
-            .initiate_session(
-                state
-                    .as_ref()
-                    .map(|s| s.keys.clone().into_iter().map(|(t, _)| t).collect::<Vec<_>>()),
-            )
-            .await?;
-
-        self.wallet =
-            WebProvider::WalletConnect(WalletConnectProvider::new(wc, self.rpc_node.clone()));
+        let chain_id = self.chain_id.unwrap_or(1);
+        let topics =
+            state.as_ref().map(|s| s.keys.clone().into_iter().map(|(t, _)| t).collect::<Vec<_>>());
+        // Session proposed through known pairing awaits wallet's approval on the pairing topic
+        let reused_pairing = match state.as_ref().map(|s| &s.state) {
+            Some(WalletConnectClientState::InitialSubscription(topic)) => {
+                let topic = topic.to_string();
+                let expiry = SessionStore::load().pairing(&topic).and_then(|p| p.expiry);
+                Some(PairingInfo::reused(topic, expiry))
+            }
+            _ => None,
+        };
+        // Client proposes namespaces of its initial state
+        let state = match state {
+            Some(state) => state,
+            None => {
+                self.wc_proposal.state(chain_id, &self.metadata).map_err(EthereumError::from)?
+            }
+        };
+        let wc = self.wc_client(chain_id, Some(state))?;
+        let url = wc.initiate_session(topics).await?;
+        let pairing = match url.is_empty() {
+            true => reused_pairing,
+            false => match PairingInfo::parse(&url, now()) {
+                Ok(pairing) => Some(pairing),
+                Err(err) => {
+                    _ = wc.disconnect().await;
+                    return Err(err.into());
+                }
+            },
+        };
 
-        if !url.is_empty() {
-            _ = self.sender.send(Event::ConnectionWaiting(url)).await;
+        let redirect = wallet.map(|wallet| WalletRedirect {
+            wallet,
+            navigate: self.navigate_to_wallet,
+            sender: self.sender.clone(),
+        });
+        let provider =
+            WalletConnectProvider::new(wc, self.rpc_node.clone()).with_redirect(redirect);
+
+        if let Some(pairing) = pairing.as_ref().filter(|p| !p.is_reused()) {
+            self.wallet = WebProvider::WalletConnect(provider);
+            _ = self.sender.send(Event::ConnectionWaiting(pairing.clone())).await;
+            self.start_pairing_refresh(pairing.clone());
+            return Ok(Some(url));
+        } else if provider.session_topic().is_none() {
+            self.wallet = WebProvider::WalletConnect(provider);
+            if let Some(pairing) = pairing {
+                _ = self.sender.send(Event::ConnectionWaiting(pairing)).await;
+            }
         } else {
+            self.chain_id = Some(provider.chain_id());
+            self.accounts = provider.accounts();
+            self.wallet = WebProvider::WalletConnect(provider);
+
             _ = self.sender.send(Event::Connected).await;
             _ = self.sender.send(Event::ChainIdChanged(self.chain_id)).await;
             _ = self.sender.send(Event::AccountsChanged(self.accounts.clone())).await;
+            self.announce_active_account(self.accounts.as_ref()).await;
         }
 
-        Ok(())
+        Ok(None)
     }
 
     async fn request_accounts(&self) -> Result<Vec<Address>, EthereumError> {
@@ -629,6 +1963,14 @@ impl Ethereum {
         }
     }
 
+    /// Fetches accounts currently exposed by connected wallet without prompting the user
+    async fn current_accounts(&self) -> Result<Vec<Address>, EthereumError> {
+        match &self.wallet {
+            WebProvider::Injected(_) => Ok(self.request("eth_accounts", ()).await?),
+            _ => self.request_accounts().await,
+        }
+    }
+
     async fn request_chain_id(&self) -> Result<U256, EthereumError> {
         match &self.wallet {
             WebProvider::None => Err(EthereumError::NotConnected),
@@ -637,29 +1979,223 @@ impl Ethereum {
         }
     }
 
-    /// Restores connection state from local storage
-    pub async fn restore(&mut self) -> bool {
-        match LocalStorage::get::<EthereumState>(STATUS_KEY) {
-            Ok(state) => {
-                match state.wc_state {
-                    None => _ = self.connect_injected().await,
-                    Some(wc_settings) => _ = self.connect_wc(Some(wc_settings)).await,
+    /// Creates WalletConnect client connected to configured relay
+    fn wc_client(
+        &self,
+        chain_id: u64,
+        state: Option<WalletConnectState>,
+    ) -> Result<WalletConnect, EthereumError> {
+        let relay_url =
+            self.wc_relay_url.as_ref().map_or(walletconnect::DEFAULT_RELAY_URL, Url::as_str);
+        let project_id = self.wc_project_id.clone().ok_or(EthereumError::Unavailable)?.into();
+        let mut metadata = self.metadata.clone();
+        metadata.verify_url = self.wc_verify_url.as_ref().map(|url| url.to_string());
+        Ok(WalletConnect::connect_to(relay_url, project_id, chain_id, metadata, state)?)
+    }
+
+    /// Reconnects broken connection with exponential backoff, disconnecting once retry budget
+    /// is spent or the wallet does not know the session anymore
+    fn start_reconnection(&self) {
+        if !self.wallet.is_some() {
+            return;
+        }
+        let Some(generation) = self.reconnection.start() else {
+            return;
+        };
+
+        let eth = self.clone();
+        spawn_local(async move {
+            let mut attempt = 1;
+            while let Some(delay) = eth.reconnect_policy.delay(attempt) {
+                _ = eth.sender.send(Event::Reconnecting(attempt)).await;
+                TimeoutFuture::new(delay).await;
+                if !eth.reconnection.is_current(generation) {
+                    return;
                 }
-                true
+
+                match eth.reconnect().await {
+                    Ok(()) => {
+                        eth.reconnection.finish(generation);
+                        _ = eth.sender.send(Event::Reconnected).await;
+                        // Accounts or chain might have changed while the connection was broken
+                        if let (Ok(chain_id), Ok(accounts)) =
+                            (eth.request_chain_id().await, eth.current_accounts().await)
+                        {
+                            if eth.reconnection.is_current(generation) {
+                                let chain_id = Some(chain_id.low_u64());
+                                _ = eth.sender.send(Event::ChainIdChanged(chain_id)).await;
+                                _ = eth.sender.send(Event::AccountsChanged(Some(accounts))).await;
+                            }
+                        }
+                        return;
+                    }
+                    Err(EthereumError::Unauthorized) => break,
+                    Err(err) => debug!("Reconnection attempt {attempt} failed {err:?}"),
+                }
+                attempt += 1;
+            }
+
+            if eth.reconnection.is_current(generation) {
+                eth.reconnection.finish(generation);
+                _ = eth.sender.send(Event::Disconnected).await;
             }
+        });
+    }
+
+    /// Single attempt to reestablish broken connection without losing the session
+    async fn reconnect(&self) -> Result<(), EthereumError> {
+        match &self.wallet {
+            WebProvider::None => Err(EthereumError::NotConnected),
+            WebProvider::Injected(provider) => {
+                if !provider.is_present() {
+                    return Err(EthereumError::Unavailable);
+                }
+                let accounts: Vec<Address> = provider.request("eth_accounts", ()).await?;
+                if accounts.is_empty() {
+                    return Err(EthereumError::Unauthorized);
+                }
+                // Wallet injected again meanwhile emits events from its new provider object
+                provider.rebind()?;
+                Ok(())
+            }
+            WebProvider::WalletConnect(provider) => {
+                let chain_id = provider.chain_id();
+                let state = provider.get_state();
+                let topics = state.keys.iter().map(|(t, _)| t.clone()).collect();
+                let client = self.wc_client(chain_id, Some(state))?;
+                let url = client.initiate_session(Some(topics)).await?;
+                // New pairing uri means the session is gone
+                if !url.is_empty() {
+                    return Err(EthereumError::Unauthorized);
+                }
+                provider.replace_client(client);
+                Ok(())
+            }
+        }
+    }
+
+    /// Restores connection state from local storage. Injected wallets are checked without
+    /// prompting the user, so connection is restored only if the wallet still authorizes the
+    /// dApp.
+    pub async fn restore(&mut self) -> RestoreOutcome {
+        let mut store = SessionStore::load();
+        if store.prune_expired(now()) {
+            store.save();
+        }
+
+        let state = match LocalStorage::get::<EthereumState>(STATUS_KEY) {
+            Ok(state) => state,
+            Err(StorageError::KeyNotFound(_)) => return RestoreOutcome::NotStored,
             Err(err) => {
                 error!("Status not loaded {err:?}!");
-                false
+                LocalStorage::delete(STATUS_KEY);
+                return RestoreOutcome::StorageCorrupted(err.into());
+            }
+        };
+
+        if let Some(expiry) = state.session_expiry.filter(|e| *e <= now()) {
+            LocalStorage::delete(STATUS_KEY);
+            return RestoreOutcome::SessionExpired(expiry);
+        }
+
+        // Connection will keep stored selection if the wallet still exposes that account
+        self.store_active_account(state.active_account);
+        let result = match state.wc_session {
+            None => self.connect_injected(state.injected_rdns.as_deref(), false).await,
+            // Session unknown to the store has to be approved again
+            Some(topic) => self.resume_session(&topic).await,
+        };
+
+        match result {
+            Ok(()) => RestoreOutcome::Restored,
+            Err(err @ EthereumError::Unavailable) => RestoreOutcome::WalletMissing(err),
+            Err(err @ EthereumError::Eip1193Error(Eip1193Error::JsNoEthereum)) => {
+                RestoreOutcome::WalletMissing(err)
             }
+            Err(err) => RestoreOutcome::NeedsUserAction(err),
         }
     }
 
     fn collect_state(&self) -> EthereumState {
         match &self.wallet {
-            WebProvider::WalletConnect(p) => {
-                EthereumState { chain_id: Some(p.chain_id()), wc_state: Some(p.get_state()) }
-            }
-            _ => EthereumState { chain_id: self.chain_id, wc_state: None },
+            WebProvider::WalletConnect(p) => EthereumState {
+                chain_id: Some(p.chain_id()),
+                wc_session: p.session_topic(),
+                active_account: self.active_account(),
+                wallet: p.wallet(),
+                injected_rdns: None,
+                session_expiry: p.session_expiry(),
+            },
+            WebProvider::Injected(p) => EthereumState {
+                chain_id: self.chain_id,
+                wc_session: None,
+                active_account: self.active_account(),
+                wallet: None,
+                injected_rdns: p.rdns().map(String::from),
+                session_expiry: None,
+            },
+            WebProvider::None => EthereumState {
+                chain_id: self.chain_id,
+                wc_session: None,
+                active_account: self.active_account(),
+                wallet: None,
+                injected_rdns: None,
+                session_expiry: None,
+            },
+        }
+    }
+}
+
+/// Waits for the wallet to grant accounts on given chain, rechecking on every session update
+async fn wait_for_chain_accounts(
+    provider: &WalletConnectProvider,
+    chain_id: u64,
+    mut updates: watch::Receiver<()>,
+) -> Option<Vec<Address>> {
+    let mut timeout = TimeoutFuture::new(CHAIN_APPROVAL_TIMEOUT_MS);
+    loop {
+        match provider.accounts_for_chain(chain_id) {
+            Some(accounts) if !accounts.is_empty() => return Some(accounts),
+            _ if !provider.approves_chain(chain_id) => return None,
+            _ => tokio::select! {
+                changed = updates.changed() => changed.ok()?,
+                _ = &mut timeout => return None,
+            },
+        }
+    }
+}
+
+/// Waits for the wallet to answer session's ping or extension, giving up after a while, as
+/// wallets gone offline never answer
+async fn wallet_answer(
+    request: impl std::future::Future<Output = Result<(), WalletConnectError>>,
+) -> Result<(), EthereumError> {
+    tokio::select! {
+        answer = request => Ok(answer?),
+        _ = TimeoutFuture::new(WALLET_ANSWER_TIMEOUT_MS) => Err(EthereumError::WalletTimeout),
+    }
+}
+
+/// Identifies WalletConnect wallet among recent ones, by explorer id or, for wallets paired by
+/// plain QR code, by url of the peer
+fn recent_wc_id(
+    wallet: &Option<explorer::WalletDescription>,
+    peer: &Option<Metadata>,
+) -> Option<String> {
+    wallet.as_ref().map(|w| w.id.clone()).or_else(|| peer.as_ref().map(|m| m.url.clone()))
+}
+
+/// Current unix timestamp
+fn now() -> u64 {
+    chrono::Utc::now().timestamp() as u64
+}
+
+/// Removes provider's session from stored sessions
+fn forget_wc_session(provider: &WalletConnectProvider) {
+    if let Some(topic) = provider.session_topic() {
+        let mut store = SessionStore::load();
+        if store.remove_session(&topic).is_some() {
+            store.save();
         }
     }
 }
@@ -674,6 +2210,26 @@ impl JsonRpcClient for Ethereum {
         method: &str,
         params: T,
     ) -> Result<R, Self::Error> {
+        if !needs_user_action(method) {
+            return self.dispatch(method, params).await;
+        }
+
+        if method == "eth_sendTransaction" {
+            if let Some(from) = self.default_sender() {
+                let params = with_default_sender(json!(params), from);
+                return self.prompted(method, self.dispatch(method, params)).await;
+            }
+        }
+        self.prompted(method, self.dispatch(method, params)).await
+    }
+}
+
+impl Ethereum {
+    async fn dispatch<T: Serialize + Send + Sync + Debug, R: DeserializeOwned + Send>(
+        &self,
+        method: &str,
+        params: T,
+    ) -> Result<R, EthereumError> {
         match &self.wallet {
             WebProvider::None => match &self.http_provider {
                 Some(provider) => Ok(provider.request(method, params).await?),
@@ -684,3 +2240,28 @@ impl JsonRpcClient for Ethereum {
         }
     }
 }
+
+/// Fills `from` field of transaction request if caller has not provided one
+fn with_default_sender(mut params: Value, from: Address) -> Value {
+    if let Some(tx) = params.get_mut(0).and_then(|tx| tx.as_object_mut()) {
+        if tx.get("from").unwrap_or(&Value::Null).is_null() {
+            tx.insert("from".to_string(), json!(from));
+        }
+    }
+    params
+}
+
+/// Checks if wallet rejected network switch because it does not know the chain (EIP-3326)
+fn is_unrecognized_chain(err: &EthereumError) -> bool {
+    const UNRECOGNIZED_CHAIN_CODE: i64 = 4902;
+
+    err.as_error_response().is_some_and(|e| {
+        e.code == UNRECOGNIZED_CHAIN_CODE
+            // Some mobile wallets wrap original error
+            || e.data
+                .as_ref()
+                .and_then(|d| d.pointer("/originalError/code"))
+                .and_then(|c| c.as_i64())
+                == Some(UNRECOGNIZED_CHAIN_CODE)
+    })
+}
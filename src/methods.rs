@@ -0,0 +1,24 @@
+/// Methods that need wallet's signer and user's approval
+const USER_ACTION_METHODS: &[&str] = &[
+    "eth_sendTransaction",
+    "eth_signTransaction",
+    "eth_sign",
+    "personal_sign",
+    "eth_signTypedData",
+    "eth_signTypedData_v3",
+    "eth_signTypedData_v4",
+    "wallet_addEthereumChain",
+    "wallet_switchEthereumChain",
+    "wallet_watchAsset",
+    "wallet_requestPermissions",
+];
+
+/// Checks if method prompts the user in the wallet
+pub(crate) fn needs_user_action(method: &str) -> bool {
+    USER_ACTION_METHODS.contains(&method)
+}
+
+/// Checks if method has to be handled by the wallet and cannot be sent to the RPC node
+pub(crate) fn requires_wallet(method: &str) -> bool {
+    needs_user_action(method) || method.starts_with("wallet_")
+}
@@ -0,0 +1,127 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, RwLock},
+};
+
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+/// Wallet request waiting for user's approval
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PromptRequest {
+    /// Identifier unique within the queue
+    pub id: u64,
+    pub method: String,
+}
+
+#[derive(Debug, Default)]
+struct Prompts {
+    next_id: u64,
+    pending: VecDeque<PromptRequest>,
+    current: Option<PromptRequest>,
+}
+
+/// Queue letting only one request prompt the user at a time, in order of arrival. Shared by all
+/// clones of `Ethereum`.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PromptQueue {
+    turn: Arc<Mutex<()>>,
+    prompts: Arc<RwLock<Prompts>>,
+}
+
+impl PromptQueue {
+    /// Adds request with given method to the end of the queue
+    pub fn enqueue(&self, method: &str) -> PromptTicket {
+        let mut prompts = self.prompts.write().unwrap_or_else(|e| e.into_inner());
+        prompts.next_id += 1;
+        let request = PromptRequest { id: prompts.next_id, method: method.to_string() };
+        prompts.pending.push_back(request.clone());
+
+        PromptTicket { queue: self.clone(), request }
+    }
+
+    /// Number of requests waiting, including the one currently prompted
+    pub fn len(&self) -> usize {
+        self.prompts.read().map(|p| p.pending.len() + p.current.iter().count()).unwrap_or_default()
+    }
+
+    pub fn current(&self) -> Option<PromptRequest> {
+        self.prompts.read().ok().and_then(|p| p.current.clone())
+    }
+}
+
+/// Place of request in the queue. Dropping it leaves the queue, either while waiting or after
+/// the prompt is closed.
+#[derive(Debug)]
+pub(crate) struct PromptTicket {
+    queue: PromptQueue,
+    request: PromptRequest,
+}
+
+impl PromptTicket {
+    pub fn request(&self) -> &PromptRequest {
+        &self.request
+    }
+
+    /// Waits until all requests queued before are closed. The request stays current until
+    /// returned guard is dropped.
+    pub async fn open(&self) -> OwnedMutexGuard<()> {
+        let turn = self.queue.turn.clone().lock_owned().await;
+        let mut prompts = self.queue.prompts.write().unwrap_or_else(|e| e.into_inner());
+        prompts.pending.retain(|r| r.id != self.request.id);
+        prompts.current = Some(self.request.clone());
+        turn
+    }
+}
+
+impl Drop for PromptTicket {
+    fn drop(&mut self) {
+        let mut prompts = self.queue.prompts.write().unwrap_or_else(|e| e.into_inner());
+        prompts.pending.retain(|r| r.id != self.request.id);
+        if prompts.current.as_ref().is_some_and(|r| r.id == self.request.id) {
+            prompts.current = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{executor::block_on, FutureExt};
+
+    #[test]
+    fn test_prompts_open_one_at_a_time() {
+        let queue = PromptQueue::default();
+
+        let sign = queue.enqueue("eth_signTypedData_v4");
+        let send = queue.enqueue("eth_sendTransaction");
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.current(), None);
+
+        let turn = block_on(sign.open());
+        assert_eq!(queue.current().unwrap().method, "eth_signTypedData_v4");
+        assert!(send.open().now_or_never().is_none());
+        assert_eq!(queue.len(), 2);
+
+        drop(turn);
+        drop(sign);
+        assert_eq!(queue.len(), 1);
+        let _turn = send.open().now_or_never().unwrap();
+        assert_eq!(queue.current().unwrap().method, "eth_sendTransaction");
+    }
+
+    #[test]
+    fn test_abandoned_request_leaves_queue() {
+        let queue = PromptQueue::default();
+
+        let first = queue.enqueue("personal_sign");
+        let second = queue.enqueue("personal_sign");
+        drop(first);
+        assert_eq!(queue.len(), 1);
+
+        let turn = block_on(second.open());
+        drop(second);
+        assert_eq!(queue.len(), 0);
+        assert_eq!(queue.current(), None);
+        drop(turn);
+    }
+}
@@ -0,0 +1,325 @@
+use data_encoding::BASE64;
+use image::{
+    codecs::png::PngEncoder, imageops::FilterType, ColorType, ImageEncoder, ImageError, Rgb,
+    RgbImage,
+};
+use qrcode_generator::QRCodeError;
+use thiserror::Error;
+
+pub use qrcode_generator::QrCodeEcc;
+
+/// Logo cannot cover more of the code than error correction is able to restore
+const MAX_LOGO_RATIO: f32 = 0.3;
+
+/// Error struct
+#[derive(Error, Debug)]
+pub enum QrError {
+    #[error("QR code generation failed: {0:?}")]
+    Generation(QRCodeError),
+
+    #[error(transparent)]
+    Encoding(#[from] ImageError),
+
+    #[error("Logo of PNG code has to be base64 encoded data url, got {0}")]
+    LogoNotEmbedded(String),
+}
+
+impl From<QRCodeError> for QrError {
+    fn from(src: QRCodeError) -> Self {
+        QrError::Generation(src)
+    }
+}
+
+/// RGB color
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Color(pub u8, pub u8, pub u8);
+
+impl Color {
+    pub const BLACK: Color = Color(0, 0, 0);
+    pub const WHITE: Color = Color(255, 255, 255);
+
+    /// Returns color in `#rrggbb` format
+    pub fn to_hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/// Logo drawn in the middle of the code
+#[derive(Clone, Debug, PartialEq)]
+pub struct QrLogo {
+    /// Image url, PNG renderer accepts only base64 encoded `data:` urls
+    pub url: String,
+    /// Part of code's width covered by the logo, capped at 0.3
+    pub ratio: f32,
+}
+
+/// Rendering options of QR code
+#[derive(Clone, Debug, PartialEq)]
+pub struct QrOptions {
+    pub ecc: QrCodeEcc,
+    /// Width of empty border in modules
+    pub quiet_zone: usize,
+    /// Size of single module in pixels
+    pub module_size: usize,
+    pub dark: Color,
+    pub light: Color,
+    pub logo: Option<QrLogo>,
+}
+
+impl Default for QrOptions {
+    fn default() -> Self {
+        Self {
+            ecc: QrCodeEcc::Low,
+            quiet_zone: 4,
+            module_size: 8,
+            dark: Color::BLACK,
+            light: Color::WHITE,
+            logo: None,
+        }
+    }
+}
+
+impl QrOptions {
+    /// Error correction level. Codes with logo need at least quartile level to stay readable.
+    fn effective_ecc(&self) -> QrCodeEcc {
+        match (&self.logo, self.ecc) {
+            (Some(_), QrCodeEcc::Low | QrCodeEcc::Medium) => QrCodeEcc::Quartile,
+            (_, ecc) => ecc,
+        }
+    }
+}
+
+/// QR code modules including quiet zone, `true` meaning dark module
+#[derive(Clone, Debug, PartialEq)]
+pub struct QrMatrix {
+    pub modules: Vec<Vec<bool>>,
+    /// Area left empty for the logo, as `(offset, size)` in modules
+    pub logo_area: Option<(usize, usize)>,
+}
+
+impl QrMatrix {
+    /// Returns width (and height) in modules
+    pub fn size(&self) -> usize {
+        self.modules.len()
+    }
+
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.modules.get(y).and_then(|row| row.get(x)).copied().unwrap_or(false)
+    }
+}
+
+/// Generates module matrix of the code
+pub fn to_matrix(data: &str, options: &QrOptions) -> Result<QrMatrix, QrError> {
+    let code = qrcode_generator::to_matrix(data, options.effective_ecc())?;
+    let size = code.len() + 2 * options.quiet_zone;
+
+    let mut modules = vec![vec![false; size]; size];
+    for (y, row) in code.iter().enumerate() {
+        for (x, dark) in row.iter().enumerate() {
+            modules[y + options.quiet_zone][x + options.quiet_zone] = *dark;
+        }
+    }
+
+    let logo_area = options.logo.as_ref().map(|logo| {
+        let code_size = code.len();
+        // Keeping logo area centred requires the same parity as the code itself
+        let mut area = (code_size as f32 * logo.ratio.clamp(0.0, MAX_LOGO_RATIO)) as usize;
+        if area % 2 != code_size % 2 {
+            area += 1;
+        }
+        let offset = options.quiet_zone + (code_size - area) / 2;
+        for row in modules.iter_mut().skip(offset).take(area) {
+            for module in row.iter_mut().skip(offset).take(area) {
+                *module = false;
+            }
+        }
+        (offset, area)
+    });
+
+    Ok(QrMatrix { modules, logo_area })
+}
+
+/// Renders code as SVG markup
+pub fn to_svg(data: &str, options: &QrOptions) -> Result<String, QrError> {
+    let matrix = to_matrix(data, options)?;
+    let size = matrix.size();
+    let pixels = size * options.module_size;
+
+    let mut path = String::new();
+    for y in 0..size {
+        for x in 0..size {
+            if matrix.is_dark(x, y) {
+                path.push_str(&format!("M{x} {y}h1v1h-1z"));
+            }
+        }
+    }
+
+    let logo = match (&options.logo, matrix.logo_area) {
+        (Some(logo), Some((offset, area))) => format!(
+            r#"<image href="{}" x="{offset}" y="{offset}" width="{area}" height="{area}" preserveAspectRatio="xMidYMid meet"/>"#,
+            escape_attribute(&logo.url)
+        ),
+        _ => String::new(),
+    };
+
+    Ok(format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {size} {size}" width="{pixels}" height="{pixels}" shape-rendering="crispEdges"><rect width="{size}" height="{size}" fill="{}"/><path d="{path}" fill="{}"/>{logo}</svg>"#,
+        options.light.to_hex(),
+        options.dark.to_hex(),
+    ))
+}
+
+/// Renders code as PNG image. Logo has to be embedded as `data:` url, as it cannot be fetched.
+pub fn to_png(data: &str, options: &QrOptions) -> Result<Vec<u8>, QrError> {
+    let matrix = to_matrix(data, options)?;
+    let mut image = to_image(&matrix, options);
+    if let (Some(logo), Some(area)) = (&options.logo, matrix.logo_area) {
+        draw_logo(&mut image, logo, area, options)?;
+    }
+
+    let mut png = Vec::new();
+    PngEncoder::new(&mut png).write_image(
+        image.as_raw(),
+        image.width(),
+        image.height(),
+        ColorType::Rgb8,
+    )?;
+    Ok(png)
+}
+
+fn to_image(matrix: &QrMatrix, options: &QrOptions) -> RgbImage {
+    let scale = options.module_size.max(1);
+    let pixels = (matrix.size() * scale) as u32;
+    let (dark, light) = (options.dark, options.light);
+
+    RgbImage::from_fn(pixels, pixels, |x, y| {
+        let Color(r, g, b) =
+            if matrix.is_dark(x as usize / scale, y as usize / scale) { dark } else { light };
+        Rgb([r, g, b])
+    })
+}
+
+/// Draws logo over the cleared area, keeping its aspect ratio and blending transparent parts
+/// with light color
+fn draw_logo(
+    image: &mut RgbImage,
+    logo: &QrLogo,
+    (offset, area): (usize, usize),
+    options: &QrOptions,
+) -> Result<(), QrError> {
+    let embedded = logo
+        .url
+        .strip_prefix("data:")
+        .and_then(|url| url.split_once(','))
+        .filter(|(media_type, _)| media_type.ends_with(";base64"))
+        .and_then(|(_, encoded)| BASE64.decode(encoded.as_bytes()).ok())
+        .ok_or_else(|| QrError::LogoNotEmbedded(logo.url.clone()))?;
+
+    let (offset, area) =
+        ((offset * options.module_size) as u32, (area * options.module_size) as u32);
+    let logo = image::load_from_memory(&embedded)?.resize(area, area, FilterType::Triangle);
+    let logo = logo.to_rgba8();
+    let (left, top) = (offset + (area - logo.width()) / 2, offset + (area - logo.height()) / 2);
+
+    let Color(r, g, b) = options.light;
+    for (x, y, pixel) in logo.enumerate_pixels() {
+        let [lr, lg, lb, alpha] = pixel.0;
+        let blend = |logo: u8, light: u8| {
+            ((logo as u32 * alpha as u32 + light as u32 * (255 - alpha as u32)) / 255) as u8
+        };
+        image.put_pixel(left + x, top + y, Rgb([blend(lr, r), blend(lg, g), blend(lb, b)]));
+    }
+    Ok(())
+}
+
+/// Renders code as PNG image encoded in data url, ready to be used as `img` source
+pub fn to_png_data_url(data: &str, options: &QrOptions) -> Result<String, QrError> {
+    Ok(format!("data:image/png;base64,{}", BASE64.encode(&to_png(data, options)?)))
+}
+
+fn escape_attribute(value: &str) -> String {
+    value.replace('&', "&amp;").replace('"', "&quot;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URI: &str = "wc:7f6e504bfad60b485450578e05678ed3e8e8c4751d3c6160be17160d63ec90f9@2?relay-protocol=irn&symKey=587d5484ce2a2a6ee3ba1962fdd7e8588e06200c46823bd18fbd67def96ad303";
+
+    #[test]
+    fn test_matrix_contains_quiet_zone() {
+        let options = QrOptions { quiet_zone: 2, ..Default::default() };
+        let without_zone = to_matrix(URI, &QrOptions { quiet_zone: 0, ..Default::default() });
+        let matrix = to_matrix(URI, &options).unwrap();
+
+        assert_eq!(matrix.size(), without_zone.unwrap().size() + 4);
+        assert!((0..matrix.size()).all(|i| !matrix.is_dark(i, 0) && !matrix.is_dark(0, i)));
+    }
+
+    #[test]
+    fn test_logo_area_is_centred_and_cleared() {
+        let logo = QrLogo { url: "https://example.org/logo.svg".to_string(), ratio: 0.9 };
+        let options = QrOptions { logo: Some(logo), ..Default::default() };
+        let matrix = to_matrix(URI, &options).unwrap();
+
+        let (offset, area) = matrix.logo_area.unwrap();
+        assert_eq!(offset * 2 + area, matrix.size());
+        assert!(area as f32 <= (matrix.size() - 8) as f32 * MAX_LOGO_RATIO + 1.0);
+        assert!((offset..offset + area).all(|i| !matrix.is_dark(i, i)));
+    }
+
+    #[test]
+    fn test_svg_uses_configured_colors() {
+        let options = QrOptions {
+            dark: Color(0x12, 0x34, 0x56),
+            light: Color(0xff, 0xee, 0xdd),
+            ..Default::default()
+        };
+        let svg = to_svg(URI, &options).unwrap();
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(r##"fill="#123456""##));
+        assert!(svg.contains(r##"fill="#ffeedd""##));
+    }
+
+    /// Red 2x1 PNG, half transparent on the right
+    fn logo_data_url() -> String {
+        let logo = image::RgbaImage::from_raw(2, 1, vec![255, 0, 0, 255, 255, 0, 0, 0]).unwrap();
+        let mut png = Vec::new();
+        PngEncoder::new(&mut png).write_image(logo.as_raw(), 2, 1, ColorType::Rgba8).unwrap();
+        format!("data:image/png;base64,{}", BASE64.encode(&png))
+    }
+
+    #[test]
+    fn test_png_draws_logo_in_its_area() {
+        let logo = QrLogo { url: logo_data_url(), ratio: 0.3 };
+        let options =
+            QrOptions { logo: Some(logo), dark: Color(0x12, 0x34, 0x56), ..Default::default() };
+        let png = to_png(URI, &options).unwrap();
+
+        let image = image::load_from_memory(&png).unwrap().to_rgb8();
+        let matrix = to_matrix(URI, &options).unwrap();
+        let pixels = (matrix.size() * options.module_size) as u32;
+        assert_eq!(image.dimensions(), (pixels, pixels));
+        assert!(image.pixels().any(|p| *p == Rgb([0x12, 0x34, 0x56])));
+
+        // Logo twice as wide as high is centred vertically, leaving light bands above and below
+        let (offset, area) = matrix.logo_area.unwrap();
+        let (start, size) =
+            ((offset * options.module_size) as u32, (area * options.module_size) as u32);
+        let middle = start + size / 2;
+        assert_eq!(*image.get_pixel(start + 1, start + 1), Rgb([255, 255, 255]));
+        assert_eq!(*image.get_pixel(start + size - 2, start + size - 2), Rgb([255, 255, 255]));
+        assert_eq!(*image.get_pixel(start + 1, middle), Rgb([255, 0, 0]));
+        assert_eq!(*image.get_pixel(start + size - 2, middle), Rgb([255, 255, 255]));
+    }
+
+    #[test]
+    fn test_png_rejects_remote_logo() {
+        let logo = QrLogo { url: "https://example.org/logo.svg".to_string(), ratio: 0.3 };
+        let options = QrOptions { logo: Some(logo), ..Default::default() };
+
+        assert!(matches!(to_png(URI, &options), Err(QrError::LogoNotEmbedded(_))));
+    }
+}
@@ -0,0 +1,111 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use crate::walletconnect::expiry::LoopGuard;
+
+/// Exponential backoff of attempts to reconnect broken connection
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+    /// Delay before the first attempt
+    pub initial_delay_ms: u32,
+    /// Upper bound of delay between attempts
+    pub max_delay_ms: u32,
+    /// Attempts made before giving up and disconnecting
+    pub max_attempts: u32,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self { initial_delay_ms: 1_000, max_delay_ms: 30_000, max_attempts: 8 }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Policy giving up right away, broken connections are disconnected
+    pub fn disabled() -> Self {
+        Self { max_attempts: 0, ..Default::default() }
+    }
+
+    /// Delay before given attempt, counted from 1. Returns `None` once retry budget is spent.
+    pub fn delay(&self, attempt: u32) -> Option<u32> {
+        if attempt == 0 || attempt > self.max_attempts {
+            return None;
+        }
+
+        let factor = 1u32.checked_shl(attempt - 1).unwrap_or(u32::MAX);
+        Some(self.initial_delay_ms.saturating_mul(factor).min(self.max_delay_ms))
+    }
+}
+
+/// Keeps single reconnection running, so repeated failures reported meanwhile do not start
+/// another one
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Reconnection {
+    guard: LoopGuard,
+    active: Arc<AtomicBool>,
+}
+
+impl Reconnection {
+    /// Starts reconnection and returns its generation, unless one is already running
+    pub fn start(&self) -> Option<u64> {
+        match self.active.swap(true, Ordering::Relaxed) {
+            true => None,
+            false => Some(self.guard.start()),
+        }
+    }
+
+    /// Checks if reconnection is running, its connection being broken meanwhile
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::Relaxed)
+    }
+
+    pub fn is_current(&self, generation: u64) -> bool {
+        self.guard.is_current(generation)
+    }
+
+    pub fn finish(&self, generation: u64) {
+        if self.is_current(generation) {
+            self.active.store(false, Ordering::Relaxed);
+        }
+    }
+
+    /// Stops running reconnection, i.e. when the user disconnects meanwhile
+    pub fn cancel(&self) {
+        self.guard.stop();
+        self.active.store(false, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_limit() {
+        let policy =
+            ReconnectPolicy { initial_delay_ms: 500, max_delay_ms: 3_000, max_attempts: 5 };
+
+        let delays = (1..=6).map(|a| policy.delay(a)).collect::<Vec<_>>();
+        assert_eq!(delays, [Some(500), Some(1_000), Some(2_000), Some(3_000), Some(3_000), None]);
+        assert_eq!(ReconnectPolicy::disabled().delay(1), None);
+        assert_eq!(ReconnectPolicy { max_attempts: 64, ..policy }.delay(64), Some(3_000));
+    }
+
+    #[test]
+    fn test_single_reconnection_runs() {
+        let reconnection = Reconnection::default();
+
+        let generation = reconnection.start().unwrap();
+        assert!(reconnection.start().is_none());
+        assert!(reconnection.is_active());
+        reconnection.finish(generation);
+        assert!(!reconnection.is_active());
+
+        let generation = reconnection.start().unwrap();
+        reconnection.cancel();
+        assert!(!reconnection.is_current(generation) && !reconnection.is_active());
+        assert!(reconnection.start().is_some());
+    }
+}
@@ -12,6 +12,12 @@ pub enum Error {
     #[error("Missing RPC provider")]
     MissingProvider,
 
+    #[error("Method {0} is not supported by the wallet")]
+    UnsupportedMethod(String),
+
+    #[error("{0} cannot be proposed to the wallet")]
+    UnsupportedProposal(String),
+
     #[error(transparent)]
     SerdeJsonError(#[from] serde_json::Error),
 
@@ -27,6 +33,9 @@ pub enum Error {
     #[error(transparent)]
     HexError(#[from] FromHexError),
 
+    #[error("Invalid pairing uri {0}")]
+    InvalidPairingUri(String),
+
     #[error("Communication error")]
     CommsError,
 }
@@ -0,0 +1,114 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+/// Lifetime of WalletConnect session, sessions are extended by it
+pub const SESSION_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+/// Sessions expiring sooner than this are extended, or reported as expiring if the wallet refuses
+pub const EXPIRING_THRESHOLD_SECS: u64 = 24 * 60 * 60;
+/// Longest delay between expiry checks, so timers suspended along with the device do not delay
+/// the report for long. The wallet is pinged on every check to keep the session alive.
+pub const MAX_CHECK_INTERVAL_SECS: u64 = 60 * 60;
+/// Time given to the wallet to answer ping or extension, wallets gone offline never answer
+pub const WALLET_ANSWER_TIMEOUT_MS: u32 = 30_000;
+
+/// State of session's expiry
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExpiryStatus {
+    Valid,
+    Expiring,
+    Expired,
+}
+
+impl ExpiryStatus {
+    /// Checks session's expiry against current unix timestamp. Sessions of unknown expiry are
+    /// treated as valid.
+    pub fn of(expiry: Option<u64>, now: u64) -> Self {
+        match expiry {
+            Some(expiry) if expiry <= now => ExpiryStatus::Expired,
+            Some(expiry) if expiry - now <= EXPIRING_THRESHOLD_SECS => ExpiryStatus::Expiring,
+            _ => ExpiryStatus::Valid,
+        }
+    }
+
+    /// Seconds until session's expiry status changes, `None` if it never does
+    pub fn until_change(expiry: Option<u64>, now: u64) -> Option<u64> {
+        let expiry = expiry?;
+        match Self::of(Some(expiry), now) {
+            ExpiryStatus::Valid => Some(expiry - EXPIRING_THRESHOLD_SECS - now),
+            ExpiryStatus::Expiring => Some(expiry - now),
+            ExpiryStatus::Expired => None,
+        }
+    }
+}
+
+/// Controls background loop, i.e. session expiry watch. Every start invalidates previously
+/// started loops.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct LoopGuard {
+    generation: Arc<AtomicU64>,
+}
+
+impl LoopGuard {
+    /// Starts new loop generation and returns its id
+    pub fn start(&self) -> u64 {
+        self.generation.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    pub fn stop(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn is_current(&self, generation: u64) -> bool {
+        self.generation.load(Ordering::Relaxed) == generation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expiry_status() {
+        let now = 1_700_000_000;
+
+        assert_eq!(ExpiryStatus::of(None, now), ExpiryStatus::Valid);
+        assert_eq!(
+            ExpiryStatus::of(Some(now + 7 * EXPIRING_THRESHOLD_SECS), now),
+            ExpiryStatus::Valid
+        );
+        assert_eq!(
+            ExpiryStatus::of(Some(now + EXPIRING_THRESHOLD_SECS), now),
+            ExpiryStatus::Expiring
+        );
+        assert_eq!(ExpiryStatus::of(Some(now), now), ExpiryStatus::Expired);
+    }
+
+    #[test]
+    fn test_expiring_is_reported_ahead_of_expiry() {
+        let now = 1_700_000_000;
+        let expiry = now + EXPIRING_THRESHOLD_SECS + 100;
+
+        assert_eq!(ExpiryStatus::until_change(Some(expiry), now), Some(100));
+        assert_eq!(
+            ExpiryStatus::until_change(Some(expiry), now + 100),
+            Some(EXPIRING_THRESHOLD_SECS)
+        );
+        assert_eq!(ExpiryStatus::until_change(Some(expiry), expiry), None);
+        assert_eq!(ExpiryStatus::until_change(None, now), None);
+    }
+
+    #[test]
+    fn test_restart_invalidates_previous_loop() {
+        let guard = LoopGuard::default();
+        let first = guard.start();
+        let second = guard.start();
+
+        assert!(!guard.is_current(first));
+        assert!(guard.is_current(second));
+
+        guard.stop();
+        assert!(!guard.is_current(second));
+    }
+}
@@ -1,27 +1,58 @@
 pub mod error;
+pub mod expiry;
+pub mod namespaces;
+pub mod pairing;
+pub mod sessions;
 
-use self::error::Error;
+use self::{
+    error::Error,
+    expiry::SESSION_TTL_SECS,
+    namespaces::{granted, GrantedNamespace},
+};
+use crate::{
+    explorer::{Platform, WalletDescription},
+    methods::{needs_user_action, requires_wallet},
+    Event as EthereumEvent,
+};
 use async_trait::async_trait;
 use ethers::{
     providers::{Http, JsonRpcClient},
     types::{Address, Signature},
     utils::{hex::decode, serialize},
 };
-use futures::channel::oneshot;
+use futures::{channel::oneshot, poll};
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::{from_value, json};
 use std::{
+    collections::BTreeMap,
     fmt::{Debug, Formatter, Result as FmtResult},
     str::FromStr,
+    sync::{Arc, RwLock},
+    task::Poll,
 };
+use tokio::sync::mpsc::Sender;
 use unsafe_send_sync::UnsafeSendSync;
-use walletconnect_client::{prelude::*, WalletConnectState};
+use walletconnect_client::{prelude::*, State, WalletConnectState};
 use wasm_bindgen_futures::spawn_local;
 
+/// The only relay WalletConnect client connects to
+pub const DEFAULT_RELAY_URL: &str = "wss://relay.walletconnect.com";
+
+/// Wallet paired through its deep link, brought to front on requests requiring user's action
+#[derive(Clone, Debug)]
+pub(crate) struct WalletRedirect {
+    pub wallet: WalletDescription,
+    /// Navigating to the wallet instead of emitting `Event::OpenWallet`
+    pub navigate: bool,
+    pub sender: Sender<EthereumEvent>,
+}
+
 #[derive(Clone)]
 pub(crate) struct WalletConnectProvider {
-    client: UnsafeSendSync<WalletConnect>,
+    /// Client is shared by all clones, so reconnection replaces it everywhere
+    client: Arc<RwLock<UnsafeSendSync<WalletConnect>>>,
     provider: Option<UnsafeSendSync<Http>>,
+    redirect: Option<Arc<WalletRedirect>>,
 }
 
 impl Debug for WalletConnectProvider {
@@ -41,18 +72,34 @@ impl JsonRpcClient for WalletConnectProvider {
     ) -> Result<R, Error> {
         let params = json!(params);
 
-        let chain_id = self.client.chain_id();
+        let chain_id = self.client().chain_id();
 
-        if self.client.supports_method(method) {
+        if self.supports_method(method) {
             let (sender, receiver) = oneshot::channel();
+            let (published, on_published) = oneshot::channel();
             let m = method.to_string();
-            let client = self.client.clone();
+            let client = self.client();
             spawn_local(async move {
-                _ = sender.send(client.request(&m, Some(params), chain_id).await)
+                let mut request = Box::pin(client.request(&m, Some(params), chain_id));
+                // First poll publishes the request, the rest awaits wallet's answer
+                let result = match poll!(request.as_mut()) {
+                    Poll::Ready(result) => result,
+                    Poll::Pending => {
+                        _ = published.send(());
+                        request.await
+                    }
+                };
+                _ = sender.send(result);
             });
+            if needs_user_action(method) && on_published.await.is_ok() {
+                self.open_wallet();
+            }
             let res = receiver.await.map_err(|_| Error::CommsError)??;
 
             Ok(from_value(res)?)
+        } else if requires_wallet(method) {
+            // Sending signing requests to the RPC node would only hide the real problem
+            Err(Error::UnsupportedMethod(method.to_string()))
         } else if let Some(provider) = &self.provider {
             Ok(provider.request(method, params).await?)
         } else {
@@ -73,45 +120,164 @@ impl WalletConnectProvider {
             }
             _ => None,
         };
-        Self { client: UnsafeSendSync::new(client), provider }
+        Self {
+            client: Arc::new(RwLock::new(UnsafeSendSync::new(client))),
+            provider,
+            redirect: None,
+        }
+    }
+
+    fn client(&self) -> UnsafeSendSync<WalletConnect> {
+        self.client.read().unwrap().clone()
+    }
+
+    /// Replaces client, i.e. with one connected to the relay again after connection was lost
+    pub fn replace_client(&self, client: WalletConnect) {
+        *self.client.write().unwrap() = UnsafeSendSync::new(client);
+    }
+
+    /// Remembers wallet used for pairing to open it on requests awaiting user's approval
+    pub fn with_redirect(mut self, redirect: Option<WalletRedirect>) -> Self {
+        self.redirect = redirect.map(Arc::new);
+        self
+    }
+
+    /// Get wallet used for pairing
+    pub fn wallet(&self) -> Option<WalletDescription> {
+        self.redirect.as_ref().map(|r| r.wallet.clone())
+    }
+
+    /// Brings paired mobile wallet to front. Desktop wallets are left alone, as the session
+    /// might have been paired with a phone by QR code.
+    fn open_wallet(&self) {
+        let Some(redirect) = &self.redirect else {
+            return;
+        };
+
+        let platform = Platform::detect();
+        if !platform.is_mobile() {
+            return;
+        }
+
+        if let Some(link) = redirect.wallet.open_link(platform) {
+            if redirect.navigate {
+                open_link(&link);
+            } else {
+                if let Err(err) = redirect.sender.try_send(EthereumEvent::OpenWallet(link)) {
+                    log::error!("Wallet link not reported {err:?}");
+                }
+            }
+        }
     }
 
     pub fn get_state(&self) -> WalletConnectState {
-        self.client.get_state()
+        self.client().get_state()
+    }
+
+    /// Checks if the session was closed, either by disconnecting or by its expiry
+    pub fn is_closed(&self) -> bool {
+        self.get_state().state == State::Disconnected
     }
 
     pub async fn disconnect(&self) {
-        _ = self.client.disconnect().await;
+        _ = self.client().disconnect().await;
+    }
+
+    /// Get namespaces granted by the wallet in approved session
+    pub fn granted_namespaces(&self) -> Option<BTreeMap<String, GrantedNamespace>> {
+        granted(&self.get_state())
+    }
+
+    /// Checks if chain is among approved session namespaces. Sessions without known namespaces
+    /// approve any chain.
+    pub fn approves_chain(&self, chain_id: u64) -> bool {
+        match self.granted_namespaces() {
+            Some(namespaces) => namespaces.values().any(|ns| ns.chain_ids().contains(&chain_id)),
+            None => true,
+        }
+    }
+
+    /// Checks if method was granted by the wallet
+    pub fn supports_method(&self, method: &str) -> bool {
+        match self.granted_namespaces() {
+            Some(namespaces) => namespaces.values().any(|ns| ns.methods.contains(method)),
+            None => self.client().supports_method(method),
+        }
+    }
+
+    /// Get topic of approved session
+    pub fn session_topic(&self) -> Option<String> {
+        match self.get_state().state {
+            State::Connected(topic) => Some(topic.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Get topic of pairing the session was proposed through
+    pub fn pairing_topic(&self) -> Option<String> {
+        let state = self.get_state();
+        match (state.session.pairing_topic, state.state) {
+            (Some(topic), _)
+            | (None, State::InitialSubscription(topic) | State::SessionProposed(topic)) => {
+                Some(topic.to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// Get metadata of connected wallet
+    pub fn peer_metadata(&self) -> Option<Metadata> {
+        self.get_state().session.controller.map(|peer| peer.metadata)
+    }
+
+    /// Get unix timestamp of session's expiry
+    pub fn session_expiry(&self) -> Option<u64> {
+        self.get_state().session.expiry.map(|expiry| expiry.timestamp() as u64)
+    }
+
+    /// Pings the wallet, checking that it is reachable and still knows the session
+    pub async fn ping(&self) -> Result<(), WalletConnectError> {
+        self.client().ping().await
+    }
+
+    /// Extends the session by its full lifetime from now, once the wallet accepts it
+    pub async fn extend(&self) -> Result<(), WalletConnectError> {
+        self.client().extend(chrono::Duration::seconds(SESSION_TTL_SECS as i64)).await
+    }
+
+    /// Proposes the session again with fresh pairing. Returns new pairing uri.
+    pub async fn propose(&self) -> Result<String, WalletConnectError> {
+        self.client().initiate_session(None).await
     }
 
     /// Get chain id
     pub fn chain_id(&self) -> u64 {
-        self.client.chain_id()
+        self.client().chain_id()
     }
 
     /// Get chain id
     pub fn set_chain_id(&mut self, chain_id: u64) {
-        self.client.set_chain_id(chain_id)
+        self.client.write().unwrap().set_chain_id(chain_id)
     }
 
     /// Get current valid address
     pub fn address(&self) -> Address {
-        self.client.address()
+        self.client().address()
     }
 
     /// Get all accounts connected to currently set chain_id
     pub fn accounts(&self) -> Option<Vec<Address>> {
-        self.accounts_for_chain(self.client.chain_id())
+        self.accounts_for_chain(self.client().chain_id())
     }
 
     /// Get all accounts available for chain id
     pub fn accounts_for_chain(&self, chain_id: u64) -> Option<Vec<Address>> {
-        self.client.get_accounts_for_chain_id(chain_id)
+        self.client().get_accounts_for_chain_id(chain_id)
     }
 
     /// Get next message
     pub async fn next(&self) -> Result<Option<Event>, WalletConnectError> {
-        self.client.next().await
+        self.client().next().await
     }
 
     /// Builds typed data Json structure to send it to WalletConnect and sends via client's channel
@@ -130,3 +296,10 @@ impl WalletConnectProvider {
         Ok(Signature::try_from(sig.as_slice())?)
     }
 }
+
+/// Navigates to wallet's deep link, i.e. one received in `Event::OpenWallet`
+pub fn open_link(link: &str) {
+    if let Err(err) = gloo_utils::window().location().set_href(link) {
+        log::error!("Could not open wallet link {err:?}");
+    }
+}
@@ -0,0 +1,300 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Deserialize;
+use serde_json::{from_value, json, Value};
+use walletconnect_client::{prelude::Metadata, WalletConnectState};
+
+use super::{error::Error, pairing::DEFAULT_RELAY_PROTOCOL};
+
+const EIP155: &str = "eip155";
+
+/// Methods requested from the wallet when none were configured
+pub const DEFAULT_METHODS: &[&str] =
+    &["eth_sendTransaction", "personal_sign", "eth_signTypedData_v4"];
+
+/// Methods optionally requested from the wallet when none were configured
+pub const DEFAULT_OPTIONAL_METHODS: &[&str] = &["eth_signTransaction", "eth_signTypedData"];
+
+/// Events requested from the wallet when none were configured
+pub const DEFAULT_EVENTS: &[&str] = &["chainChanged", "accountsChanged"];
+
+/// Chains (in CAIP-2 format), methods and events requested for a single namespace
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NamespaceProposal {
+    pub chains: BTreeSet<String>,
+    pub methods: BTreeSet<String>,
+    pub events: BTreeSet<String>,
+}
+
+/// Required and optional namespaces of WalletConnect session proposal, keyed by namespace name
+/// (i.e. `eip155`)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SessionProposal {
+    pub required: BTreeMap<String, NamespaceProposal>,
+    pub optional: BTreeMap<String, NamespaceProposal>,
+}
+
+impl SessionProposal {
+    /// Adds chains, methods and events the wallet has to support to approve the session
+    pub fn require(&mut self, chains: &[&str], methods: &[&str], events: &[&str]) {
+        add_to(&mut self.required, chains, methods, events);
+    }
+
+    /// Adds chains, methods and events the wallet may support
+    pub fn request_optional(&mut self, chains: &[&str], methods: &[&str], events: &[&str]) {
+        add_to(&mut self.optional, chains, methods, events);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.required.is_empty() && self.optional.is_empty()
+    }
+
+    /// Configured proposal or, without any configuration, one asking the wallet for default
+    /// methods and events on given chain
+    fn or_default(&self, chain_id: u64) -> Self {
+        if !self.is_empty() {
+            return self.clone();
+        }
+
+        let chain = format!("{EIP155}:{chain_id}");
+        let mut default = Self::default();
+        default.require(&[&chain], DEFAULT_METHODS, DEFAULT_EVENTS);
+        default.request_optional(&[&chain], DEFAULT_OPTIONAL_METHODS, &[]);
+        default
+    }
+
+    /// Builds client's initial state carrying the proposal, as WalletConnect client proposes
+    /// the namespaces of its stored session. Fails if the proposal asks for chains other than
+    /// EVM ones.
+    pub(crate) fn state(
+        &self,
+        chain_id: u64,
+        metadata: &Metadata,
+    ) -> Result<WalletConnectState, Error> {
+        let proposal = self.or_default(chain_id);
+        proposal.check()?;
+
+        let session = json!({
+            "relay": { "protocol": DEFAULT_RELAY_PROTOCOL },
+            "namespaces": null,
+            "requiredNamespaces": to_json(&proposal.required),
+            "optionalNamespaces": to_json(&proposal.optional),
+            "pairingTopic": null,
+            "proposer": { "publicKey": "", "metadata": metadata },
+            "controller": null,
+            "expiry": null,
+            "chainId": chain_id,
+        });
+        client_state(json!({ "state": "Connecting", "keys": [], "session": session }))
+    }
+
+    fn check(&self) -> Result<(), Error> {
+        for (name, ns) in self.required.iter().chain(&self.optional) {
+            if let Some(unsupported) = ns.chains.iter().find(|c| !c.starts_with("eip155:")) {
+                return Err(Error::UnsupportedProposal(unsupported.clone()));
+            }
+            if name != EIP155 {
+                return Err(Error::UnsupportedProposal(name.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Namespace granted by the wallet after session approval
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GrantedNamespace {
+    /// Accounts in CAIP-10 format, i.e. `eip155:1:0xab16a96d359ec26a11e2c2b3d8f8b8942d5bfcdb`
+    pub accounts: BTreeSet<String>,
+    pub chains: BTreeSet<String>,
+    pub methods: BTreeSet<String>,
+    pub events: BTreeSet<String>,
+}
+
+impl GrantedNamespace {
+    /// Returns ids of granted EVM chains
+    pub fn chain_ids(&self) -> Vec<u64> {
+        self.chains
+            .iter()
+            .filter_map(|c| c.strip_prefix("eip155:").and_then(|id| id.parse().ok()))
+            .collect()
+    }
+}
+
+/// Namespace as stored in client's session
+#[derive(Deserialize)]
+struct SettledNamespace {
+    #[serde(default)]
+    accounts: BTreeSet<String>,
+    chains: Option<BTreeSet<String>>,
+    #[serde(default)]
+    methods: BTreeSet<String>,
+    #[serde(default)]
+    events: BTreeSet<String>,
+}
+
+/// Builds client state from its JSON form. Client types borrow strings while deserializing, so
+/// they are parsed from text rather than from `Value`.
+pub(crate) fn client_state(state: Value) -> Result<WalletConnectState, Error> {
+    Ok(serde_json::from_str(&state.to_string())?)
+}
+
+/// Reads namespaces settled by the wallet from client's state. Chains not listed explicitly are
+/// taken from accounts.
+pub(crate) fn granted(state: &WalletConnectState) -> Option<BTreeMap<String, GrantedNamespace>> {
+    let namespaces = serde_json::to_value(&state.session.namespaces).ok()?;
+    let namespaces: Option<BTreeMap<String, SettledNamespace>> = from_value(namespaces).ok()?;
+
+    let granted = namespaces?
+        .into_iter()
+        .map(|(name, ns)| {
+            let chains = ns.chains.unwrap_or_else(|| {
+                ns.accounts
+                    .iter()
+                    .filter_map(|a| a.rsplit_once(':').map(|(chain, _)| chain.to_string()))
+                    .collect()
+            });
+            let granted = GrantedNamespace {
+                accounts: ns.accounts,
+                chains,
+                methods: ns.methods,
+                events: ns.events,
+            };
+            (name, granted)
+        })
+        .collect();
+    Some(granted)
+}
+
+fn add_to(
+    target: &mut BTreeMap<String, NamespaceProposal>,
+    chains: &[&str],
+    methods: &[&str],
+    events: &[&str],
+) {
+    let mut names = chains
+        .iter()
+        .map(|c| c.split_once(':').map(|(ns, _)| ns).unwrap_or(EIP155))
+        .collect::<BTreeSet<_>>();
+    if names.is_empty() {
+        names.insert(EIP155);
+    }
+
+    for name in names {
+        let namespace = target.entry(name.to_string()).or_default();
+        namespace.chains.extend(
+            chains
+                .iter()
+                .filter(|c| c.split_once(':').map(|(ns, _)| ns) == Some(name))
+                .map(|c| c.to_string()),
+        );
+        namespace.methods.extend(methods.iter().map(|m| m.to_string()));
+        namespace.events.extend(events.iter().map(|e| e.to_string()));
+    }
+}
+
+fn to_json(namespaces: &BTreeMap<String, NamespaceProposal>) -> Value {
+    namespaces
+        .iter()
+        .map(|(name, ns)| {
+            let namespace = json!({
+                "chains": ns.chains,
+                "methods": ns.methods,
+                "events": ns.events,
+            });
+            (name.clone(), namespace)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use url::Url;
+
+    fn metadata() -> Metadata {
+        Metadata::from("dApp", "Test dApp", Url::parse("https://example.org").unwrap(), vec![])
+    }
+
+    fn proposed(state: &WalletConnectState) -> Value {
+        serde_json::to_value(&state.session).unwrap()
+    }
+
+    #[test]
+    fn test_default_proposal_requires_current_chain() {
+        let state = SessionProposal::default().state(10, &metadata()).unwrap();
+        let session = proposed(&state);
+
+        let required = &session["requiredNamespaces"]["eip155"];
+        assert_eq!(required["chains"], json!(["eip155:10"]));
+        assert!(required["methods"].as_array().unwrap().contains(&json!("eth_sendTransaction")));
+        assert!(required["events"].as_array().unwrap().contains(&json!("accountsChanged")));
+
+        let optional = &session["optionalNamespaces"]["eip155"];
+        assert_eq!(optional["chains"], json!(["eip155:10"]));
+        assert!(optional["methods"].as_array().unwrap().contains(&json!("eth_signTransaction")));
+        assert_eq!(session["proposer"]["metadata"]["name"], "dApp");
+    }
+
+    #[test]
+    fn test_configured_chains_are_proposed() {
+        let mut proposal = SessionProposal::default();
+        proposal.require(&["eip155:1"], &["personal_sign"], &["chainChanged"]);
+        proposal.request_optional(&["eip155:137", "eip155:8453"], &["eth_sendTransaction"], &[]);
+
+        let session = proposed(&proposal.state(1, &metadata()).unwrap());
+        assert_eq!(session["requiredNamespaces"]["eip155"]["chains"], json!(["eip155:1"]));
+        assert_eq!(
+            session["optionalNamespaces"]["eip155"]["chains"],
+            json!(["eip155:137", "eip155:8453"])
+        );
+    }
+
+    #[test]
+    fn test_any_method_and_event_is_proposed() {
+        let mut proposal = SessionProposal::default();
+        proposal.require(&["eip155:1"], &["personal_sign"], &["chainChanged"]);
+        proposal.request_optional(
+            &["eip155:1"],
+            &["wallet_switchEthereumChain", "wallet_addEthereumChain"],
+            &["message"],
+        );
+
+        let session = proposed(&proposal.state(1, &metadata()).unwrap());
+        let optional = &session["optionalNamespaces"]["eip155"];
+        assert_eq!(
+            optional["methods"],
+            json!(["wallet_addEthereumChain", "wallet_switchEthereumChain"])
+        );
+        assert_eq!(optional["events"], json!(["message"]));
+    }
+
+    #[test]
+    fn test_granted_methods_are_read_from_settled_session() {
+        let mut state = SessionProposal::default().state(1, &metadata()).unwrap();
+        let account = "eip155:1:0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266";
+        let settled = json!({
+            "eip155": {
+                "accounts": [account],
+                "methods": ["personal_sign", "wallet_switchEthereumChain"],
+                "events": ["chainChanged", "message"],
+            }
+        });
+        state.session.namespaces = Some(serde_json::from_str(&settled.to_string()).unwrap());
+
+        let granted = &granted(&state).unwrap()["eip155"];
+        assert!(granted.methods.contains("wallet_switchEthereumChain"));
+        assert!(granted.events.contains("message"));
+        assert_eq!(granted.chain_ids(), vec![1]);
+    }
+
+    #[test]
+    fn test_non_evm_proposal_is_rejected() {
+        let mut proposal = SessionProposal::default();
+        proposal.request_optional(&["solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp"], &[], &[]);
+        assert!(matches!(
+            proposal.state(1, &metadata()),
+            Err(Error::UnsupportedProposal(c)) if c.starts_with("solana:")
+        ));
+    }
+}
@@ -0,0 +1,104 @@
+use url::Url;
+
+use super::error::Error;
+
+/// Lifetime of pairing proposals that do not state their expiry
+pub const PAIRING_TTL_SECS: u64 = 5 * 60;
+/// Relay protocol used by WalletConnect v2
+pub const DEFAULT_RELAY_PROTOCOL: &str = "irn";
+
+/// Pairing awaiting wallet's approval
+#[derive(Clone, Debug, PartialEq)]
+pub struct PairingInfo {
+    /// Pairing uri to be shown as QR code or passed to the wallet. Missing if the session was
+    /// proposed through already known pairing.
+    pub uri: Option<String>,
+    pub topic: String,
+    /// Unix timestamp of pairing's expiry
+    pub expiry: Option<u64>,
+    pub relay_protocol: String,
+}
+
+impl PairingInfo {
+    /// Parses WalletConnect v2 pairing uri, i.e.
+    /// `wc:{topic}@2?relay-protocol=irn&symKey={key}&expiryTimestamp={timestamp}`
+    pub fn parse(uri: &str, now: u64) -> Result<Self, Error> {
+        let invalid = || Error::InvalidPairingUri(uri.to_string());
+
+        let url = Url::parse(uri).map_err(|_| invalid())?;
+        if url.scheme() != "wc" {
+            return Err(invalid());
+        }
+
+        let (topic, version) = url.path().split_once('@').ok_or_else(invalid)?;
+        if topic.is_empty() || version != "2" {
+            return Err(invalid());
+        }
+
+        let param = |name: &str| url.query_pairs().find(|(k, _)| k == name).map(|(_, v)| v);
+        let relay_protocol = param("relay-protocol").ok_or_else(invalid)?.into_owned();
+        let expiry = match param("expiryTimestamp") {
+            Some(expiry) => expiry.parse().map_err(|_| invalid())?,
+            None => now + PAIRING_TTL_SECS,
+        };
+
+        Ok(Self {
+            uri: Some(uri.to_string()),
+            topic: topic.to_string(),
+            expiry: Some(expiry),
+            relay_protocol,
+        })
+    }
+
+    /// Describes already known pairing the session was proposed through
+    pub fn reused(topic: String, expiry: Option<u64>) -> Self {
+        Self { uri: None, topic, expiry, relay_protocol: DEFAULT_RELAY_PROTOCOL.to_string() }
+    }
+
+    /// Checks if the pairing has been reused instead of created with new uri
+    pub fn is_reused(&self) -> bool {
+        self.uri.is_none()
+    }
+
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expiry.map(|e| e <= now).unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOPIC: &str = "7f6e504bfad60b485450578e05678ed3e8e8c4751d3c6160be17160d63ec90f9";
+    const KEY: &str = "587d5484ce2a2a6ee3ba1962fdd7e8588e06200c46823bd18fbd67def96ad303";
+
+    #[test]
+    fn test_parse_pairing_uri() {
+        let uri =
+            format!("wc:{TOPIC}@2?relay-protocol=irn&symKey={KEY}&expiryTimestamp=1705000000");
+        let pairing = PairingInfo::parse(&uri, 1_700_000_000).unwrap();
+
+        assert_eq!(pairing.uri.as_deref(), Some(uri.as_str()));
+        assert_eq!(pairing.topic, TOPIC);
+        assert_eq!(pairing.relay_protocol, "irn");
+        assert_eq!(pairing.expiry, Some(1_705_000_000));
+        assert!(!pairing.is_reused());
+    }
+
+    #[test]
+    fn test_parse_pairing_uri_without_expiry() {
+        let uri = format!("wc:{TOPIC}@2?relay-protocol=irn&symKey={KEY}");
+        let pairing = PairingInfo::parse(&uri, 1_000).unwrap();
+
+        assert_eq!(pairing.expiry, Some(1_000 + PAIRING_TTL_SECS));
+        assert!(!pairing.is_expired(1_000));
+        assert!(pairing.is_expired(1_000 + PAIRING_TTL_SECS));
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_uris() {
+        assert!(PairingInfo::parse(&format!("wc:{TOPIC}@1?bridge=x&key={KEY}"), 0).is_err());
+        assert!(PairingInfo::parse(&format!("wc:{TOPIC}@2?symKey={KEY}"), 0).is_err());
+        assert!(PairingInfo::parse("https://example.org", 0).is_err());
+    }
+}
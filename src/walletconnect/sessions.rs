@@ -0,0 +1,275 @@
+use ethers::types::Address;
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, to_value, Value};
+use walletconnect_client::{prelude::*, WalletConnectState};
+
+use super::{error::Error, namespaces::client_state, WalletConnectProvider};
+use crate::explorer::WalletDescription;
+
+/// Local storage key of known sessions and pairings
+const SESSIONS_KEY: &str = "ETHERS_WEB_WC_SESSIONS";
+/// Lifetime of pairings used to establish a session
+pub const PAIRING_ACTIVE_TTL_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// WalletConnect session that can be resumed
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StoredSession {
+    pub topic: String,
+    pub pairing_topic: Option<String>,
+    /// Metadata of the wallet on the other side
+    pub peer: Option<Metadata>,
+    /// Unix timestamp of session's expiry
+    pub expiry: Option<u64>,
+    pub chain_id: u64,
+    pub active_account: Option<Address>,
+    pub wallet: Option<WalletDescription>,
+    /// Symmetric key of the session topic
+    key: Value,
+    /// Session as settled by the wallet
+    session: Value,
+}
+
+impl StoredSession {
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expiry.map(|e| e <= now).unwrap_or(false)
+    }
+
+    /// Client state connected to the session topic
+    pub(crate) fn state(&self) -> Result<WalletConnectState, Error> {
+        client_state(json!({
+            "state": { "Connected": self.topic },
+            "keys": [[self.topic, self.key]],
+            "session": self.session,
+        }))
+    }
+}
+
+/// WalletConnect pairing that can be reused to propose new sessions
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StoredPairing {
+    pub topic: String,
+    pub peer: Option<Metadata>,
+    /// Unix timestamp of pairing's expiry
+    pub expiry: Option<u64>,
+    pub wallet: Option<WalletDescription>,
+    /// Symmetric key of the pairing topic
+    key: Value,
+    /// Key the wallet knows the dApp by
+    public_key: String,
+}
+
+impl StoredPairing {
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expiry.map(|e| e <= now).unwrap_or(false)
+    }
+
+    /// Client state proposing given session through the pairing topic once subscribed to it
+    pub(crate) fn state(&self, proposal: WalletConnectState) -> Result<WalletConnectState, Error> {
+        let mut session = to_value(&proposal.session)?;
+        session["proposer"]["publicKey"] = json!(self.public_key);
+
+        client_state(json!({
+            "state": { "InitialSubscription": self.topic },
+            "keys": [[self.topic, self.key]],
+            "session": session,
+        }))
+    }
+}
+
+/// Sessions and pairings persisted in local storage
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SessionStore {
+    pub sessions: Vec<StoredSession>,
+    pub pairings: Vec<StoredPairing>,
+}
+
+impl SessionStore {
+    pub fn load() -> Self {
+        LocalStorage::get(SESSIONS_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        _ = LocalStorage::set(SESSIONS_KEY, self);
+    }
+
+    pub fn session(&self, topic: &str) -> Option<&StoredSession> {
+        self.sessions.iter().find(|s| s.topic == topic)
+    }
+
+    pub fn pairing(&self, topic: &str) -> Option<&StoredPairing> {
+        self.pairings.iter().find(|p| p.topic == topic)
+    }
+
+    /// Stores session and its pairing, replacing previously known ones of the same topics
+    pub fn upsert(&mut self, session: StoredSession, pairing: Option<StoredPairing>) {
+        self.sessions.retain(|s| s.topic != session.topic);
+        self.sessions.push(session);
+
+        if let Some(pairing) = pairing {
+            self.pairings.retain(|p| p.topic != pairing.topic);
+            self.pairings.push(pairing);
+        }
+    }
+
+    pub fn remove_session(&mut self, topic: &str) -> Option<StoredSession> {
+        let index = self.sessions.iter().position(|s| s.topic == topic)?;
+        Some(self.sessions.remove(index))
+    }
+
+    /// Removes pairing along with all sessions established through it
+    pub fn remove_pairing(&mut self, topic: &str) -> Option<StoredPairing> {
+        self.sessions.retain(|s| s.pairing_topic.as_deref() != Some(topic));
+        let index = self.pairings.iter().position(|p| p.topic == topic)?;
+        Some(self.pairings.remove(index))
+    }
+
+    /// Forgets expired sessions and pairings. Returns `true` if anything was removed.
+    pub fn prune_expired(&mut self, now: u64) -> bool {
+        let count = self.sessions.len() + self.pairings.len();
+        self.sessions.retain(|s| !s.is_expired(now));
+        self.pairings.retain(|p| !p.is_expired(now));
+        count != self.sessions.len() + self.pairings.len()
+    }
+
+    /// Records session of connected provider. Known pairing keeps its expiry.
+    pub(crate) fn record(
+        &mut self,
+        provider: &WalletConnectProvider,
+        active_account: Option<Address>,
+        now: u64,
+    ) -> bool {
+        let Some(topic) = provider.session_topic() else {
+            return false;
+        };
+
+        let state = provider.get_state();
+        let key_of = |topic: &str| {
+            let (_, key) = state.keys.iter().find(|(t, _)| t.to_string() == topic)?;
+            to_value(key).ok()
+        };
+        let (Some(key), Ok(session)) = (key_of(&topic), to_value(&state.session)) else {
+            return false;
+        };
+
+        let pairing_topic = provider.pairing_topic();
+        let pairing = pairing_topic.as_ref().and_then(|pairing_topic| {
+            let expiry = match self.pairing(pairing_topic) {
+                Some(known) => known.expiry,
+                None => Some(now + PAIRING_ACTIVE_TTL_SECS),
+            };
+            Some(StoredPairing {
+                topic: pairing_topic.clone(),
+                peer: provider.peer_metadata(),
+                expiry,
+                wallet: provider.wallet(),
+                key: key_of(pairing_topic)?,
+                public_key: state.session.proposer.public_key.clone(),
+            })
+        });
+
+        self.upsert(
+            StoredSession {
+                topic,
+                pairing_topic,
+                peer: provider.peer_metadata(),
+                expiry: provider.session_expiry(),
+                chain_id: provider.chain_id(),
+                active_account,
+                wallet: provider.wallet(),
+                key,
+                session,
+            },
+            pairing,
+        );
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::walletconnect::namespaces::SessionProposal;
+    use url::Url;
+
+    fn proposal() -> WalletConnectState {
+        let metadata =
+            Metadata::from("dApp", "Test dApp", Url::parse("https://example.org").unwrap(), vec![]);
+        SessionProposal::default().state(1, &metadata).unwrap()
+    }
+
+    fn session(topic: &str, pairing: &str, expiry: Option<u64>) -> StoredSession {
+        StoredSession {
+            topic: topic.to_string(),
+            pairing_topic: Some(pairing.to_string()),
+            peer: None,
+            expiry,
+            chain_id: 1,
+            active_account: None,
+            wallet: None,
+            key: to_value([7u8; 32]).unwrap(),
+            session: to_value(proposal().session).unwrap(),
+        }
+    }
+
+    fn pairing(topic: &str, expiry: Option<u64>) -> StoredPairing {
+        StoredPairing {
+            topic: topic.to_string(),
+            peer: None,
+            expiry,
+            wallet: None,
+            key: to_value([7u8; 32]).unwrap(),
+            public_key: "dapp".to_string(),
+        }
+    }
+
+    fn topics(store: &SessionStore) -> (Vec<String>, Vec<String>) {
+        (
+            store.sessions.iter().map(|s| s.topic.clone()).collect(),
+            store.pairings.iter().map(|p| p.topic.clone()).collect(),
+        )
+    }
+
+    #[test]
+    fn test_upsert_replaces_known_session() {
+        let mut store = SessionStore::default();
+        store.upsert(session("a", "p", None), Some(pairing("p", None)));
+        store.upsert(session("a", "p", Some(10)), Some(pairing("p", Some(20))));
+        store.upsert(session("b", "p", None), None);
+
+        assert_eq!(topics(&store), (vec!["a".into(), "b".into()], vec!["p".into()]));
+        assert_eq!(store.session("a").unwrap().expiry, Some(10));
+    }
+
+    #[test]
+    fn test_remove_pairing_drops_its_sessions() {
+        let mut store = SessionStore::default();
+        store.upsert(session("a", "p1", None), Some(pairing("p1", None)));
+        store.upsert(session("b", "p2", None), Some(pairing("p2", None)));
+
+        assert!(store.remove_pairing("p1").is_some());
+        assert_eq!(topics(&store), (vec!["b".into()], vec!["p2".into()]));
+    }
+
+    #[test]
+    fn test_prune_expired() {
+        let mut store = SessionStore::default();
+        store.upsert(session("a", "p", Some(100)), Some(pairing("p", Some(1_000))));
+        store.upsert(session("b", "p", None), None);
+
+        assert!(!store.prune_expired(50));
+        assert!(store.prune_expired(100));
+        assert_eq!(topics(&store), (vec!["b".into()], vec!["p".into()]));
+    }
+
+    #[test]
+    fn test_stored_topics_resume_client_state() {
+        let state = session("a", "p", None).state().unwrap();
+        assert_eq!(state.state, walletconnect_client::State::Connected("a".into()));
+        assert_eq!(state.keys.len(), 1);
+
+        let state = pairing("p", None).state(proposal()).unwrap();
+        assert_eq!(state.state, walletconnect_client::State::InitialSubscription("p".into()));
+        assert_eq!(state.session.proposer.public_key, "dapp");
+    }
+}
@@ -0,0 +1,375 @@
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    explorer::{Platform, WalletDescription},
+    WalletType,
+};
+
+/// Compatibility flag set by many wallets besides MetaMask itself
+const METAMASK_FLAG: &str = "isMetaMask";
+/// Local storage key of recently used wallets, kept after disconnecting
+const RECENT_WALLETS_KEY: &str = "ETHERS_WEB_RECENT_WALLETS";
+/// Number of remembered wallets
+pub const MAX_RECENT_WALLETS: usize = 5;
+
+/// Browser extension announced with EIP-6963
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProviderInfo {
+    pub uuid: String,
+    pub name: String,
+    /// Logo as data uri
+    pub icon: String,
+    pub rdns: String,
+}
+
+/// Browser extensions detected in current context
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InjectedWallets {
+    /// Extensions announced with EIP-6963
+    pub announced: Vec<ProviderInfo>,
+    /// Flags set on `window.ethereum`
+    pub flags: Vec<String>,
+    /// Whether `window.ethereum` is present
+    pub available: bool,
+}
+
+/// The way wallet option is connected to
+#[derive(Clone, Debug, PartialEq)]
+pub enum WalletAction {
+    /// Wallet is installed as browser extension, selected by its EIP-6963 rdns if announced
+    Injected(Option<String>),
+    /// Wallet is reachable only through WalletConnect, opened with pairing link if platform
+    /// allows, scanning pairing QR code otherwise
+    WalletConnect,
+    /// Wallet has to be installed from given link first, as there is no link to open it on
+    /// this platform
+    Install(String),
+}
+
+/// Wallet to be listed in wallet picker
+#[derive(Clone, Debug, PartialEq)]
+pub struct WalletOption {
+    /// Explorer id, or rdns of extension unknown to explorer
+    pub id: String,
+    pub name: String,
+    /// Logo url or data uri
+    pub icon: Option<String>,
+    /// Explorer entry of the wallet
+    pub wallet: Option<WalletDescription>,
+    pub action: WalletAction,
+    /// Unix timestamp of last connection, if the wallet was used recently
+    pub last_used: Option<u64>,
+}
+
+impl WalletOption {
+    pub fn is_installed(&self) -> bool {
+        matches!(self.action, WalletAction::Injected(_))
+    }
+
+    /// Checks if option stands for given recently used wallet
+    pub fn is_recent(&self, recent: &RecentWallet) -> bool {
+        match (&self.action, recent.wallet_type) {
+            (WalletAction::Injected(rdns), WalletType::Injected) => rdns == &recent.id,
+            (_, WalletType::WalletConnect) => recent.id.as_ref() == Some(&self.id),
+            _ => false,
+        }
+    }
+}
+
+/// Wallet the user connected to before
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecentWallet {
+    pub wallet_type: WalletType,
+    /// Explorer id of WalletConnect wallet, or EIP-6963 rdns of browser extension
+    pub id: Option<String>,
+    pub name: String,
+    /// Explorer entry of WalletConnect wallet, used for reconnecting
+    pub wallet: Option<WalletDescription>,
+    /// Unix timestamp of last connection
+    pub last_used: u64,
+}
+
+impl RecentWallet {
+    fn is_same(&self, other: &RecentWallet) -> bool {
+        self.wallet_type == other.wallet_type && self.id == other.id
+    }
+}
+
+/// Recently used wallets persisted in local storage, the last used first
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RecentWallets {
+    pub wallets: Vec<RecentWallet>,
+}
+
+impl RecentWallets {
+    pub fn load() -> Self {
+        LocalStorage::get(RECENT_WALLETS_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        _ = LocalStorage::set(RECENT_WALLETS_KEY, self);
+    }
+
+    pub fn clear() {
+        LocalStorage::delete(RECENT_WALLETS_KEY);
+    }
+
+    pub fn last(&self) -> Option<&RecentWallet> {
+        self.wallets.first()
+    }
+
+    /// Moves wallet to the front, forgetting the oldest ones above the limit
+    pub fn record(&mut self, wallet: RecentWallet) {
+        self.wallets.retain(|w| !w.is_same(&wallet));
+        self.wallets.insert(0, wallet);
+        self.wallets.truncate(MAX_RECENT_WALLETS);
+    }
+
+    pub fn remove(&mut self, wallet: &RecentWallet) -> bool {
+        let count = self.wallets.len();
+        self.wallets.retain(|w| !w.is_same(wallet));
+        count != self.wallets.len()
+    }
+}
+
+/// Moves recently used wallets to the front of options, the last used first
+pub fn prefer_recent(options: &mut [WalletOption], recent: &[RecentWallet]) {
+    for option in options.iter_mut() {
+        option.last_used = recent.iter().find(|r| option.is_recent(r)).map(|r| r.last_used);
+    }
+    options.sort_by_key(|o| std::cmp::Reverse(o.last_used));
+}
+
+/// Merges explorer wallets with detected browser extensions. Installed wallets go first, the rest
+/// keeps explorer's order. Wallets without link on given platform are offered for installation,
+/// falling back to WalletConnect's QR code if they cannot be installed either. Explorer wallets
+/// which cannot be reached nor installed are skipped.
+pub fn wallet_options(
+    wallets: &[WalletDescription],
+    injected: &InjectedWallets,
+    platform: Platform,
+    walletconnect: bool,
+) -> Vec<WalletOption> {
+    let flagged = flagged_wallet(wallets, injected);
+    let mut installed = vec![];
+    let mut others = vec![];
+
+    for wallet in wallets {
+        let announced = wallet
+            .rdns
+            .as_ref()
+            .and_then(|rdns| injected.announced.iter().find(|info| &info.rdns == rdns));
+        let option = |icon: Option<String>, action| WalletOption {
+            id: wallet.id.clone(),
+            name: wallet.name.clone(),
+            icon,
+            wallet: Some(wallet.clone()),
+            action,
+            last_used: None,
+        };
+
+        if let Some(info) = announced {
+            installed.push(option(
+                Some(info.icon.clone()),
+                WalletAction::Injected(Some(info.rdns.clone())),
+            ));
+        } else if flagged == Some(wallet.id.as_str()) {
+            installed.push(option(None, WalletAction::Injected(None)));
+        } else if walletconnect && wallet.supports(platform) {
+            others.push(option(None, WalletAction::WalletConnect));
+        } else if let Some(link) = wallet.install_link(platform) {
+            others.push(option(None, WalletAction::Install(link)));
+        } else if walletconnect {
+            others.push(option(None, WalletAction::WalletConnect));
+        }
+    }
+
+    // Extensions explorer does not know about are still worth listing
+    for info in &injected.announced {
+        if !wallets.iter().any(|w| w.rdns.as_ref() == Some(&info.rdns)) {
+            installed.push(WalletOption {
+                id: info.rdns.clone(),
+                name: info.name.clone(),
+                icon: Some(info.icon.clone()),
+                wallet: None,
+                action: WalletAction::Injected(Some(info.rdns.clone())),
+                last_used: None,
+            });
+        }
+    }
+
+    if injected.available && injected.announced.is_empty() && flagged.is_none() {
+        installed.push(WalletOption {
+            id: "injected".to_string(),
+            name: "Browser Wallet".to_string(),
+            icon: None,
+            wallet: None,
+            action: WalletAction::Injected(None),
+            last_used: None,
+        });
+    }
+
+    installed.extend(others);
+    installed
+}
+
+/// Explorer wallet recognized by flags of `window.ethereum`, used for extensions not supporting
+/// EIP-6963. Wallets relying only on `isMetaMask` match when no other wallet does, as many
+/// extensions set it for compatibility.
+fn flagged_wallet<'a>(
+    wallets: &'a [WalletDescription],
+    injected: &InjectedWallets,
+) -> Option<&'a str> {
+    if !injected.available || !injected.announced.is_empty() {
+        return None;
+    }
+
+    let candidates = wallets
+        .iter()
+        .filter(|w| !w.injected_flags.is_empty())
+        .filter(|w| w.injected_flags.iter().all(|f| injected.flags.contains(f)))
+        .collect::<Vec<_>>();
+
+    candidates
+        .iter()
+        .find(|w| w.injected_flags.iter().any(|f| f != METAMASK_FLAG))
+        .or_else(|| candidates.first())
+        .map(|w| w.id.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::explorer::AppLinks;
+
+    fn wallet(id: &str, rdns: Option<&str>, flags: &[&str]) -> WalletDescription {
+        WalletDescription {
+            id: id.to_string(),
+            short_name: id.to_string(),
+            name: id.to_string(),
+            chains: vec![1],
+            image_id: format!("{id}-logo"),
+            project_id: "project".to_string(),
+            desktop_schema: None,
+            desktop_universal: None,
+            mobile_schema: Some(format!("{id}://")),
+            mobile_universal: None,
+            rdns: rdns.map(String::from),
+            injected_flags: flags.iter().map(|f| f.to_string()).collect(),
+            app: AppLinks {
+                chrome: Some(format!("https://chrome.example/{id}")),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn info(rdns: &str) -> ProviderInfo {
+        ProviderInfo {
+            uuid: "uuid".to_string(),
+            name: rdns.to_string(),
+            icon: "data:image/svg+xml,".to_string(),
+            rdns: rdns.to_string(),
+        }
+    }
+
+    fn wallets() -> Vec<WalletDescription> {
+        vec![
+            wallet("metamask", Some("io.metamask"), &["isMetaMask"]),
+            wallet("brave", Some("com.brave.wallet"), &["isBraveWallet"]),
+            wallet("ledger", None, &[]),
+        ]
+    }
+
+    #[test]
+    fn test_announced_wallets_go_first() {
+        let injected = InjectedWallets {
+            announced: vec![info("com.brave.wallet"), info("xyz.unknown")],
+            flags: vec!["isBraveWallet".to_string(), "isMetaMask".to_string()],
+            available: true,
+        };
+
+        let options = wallet_options(&wallets(), &injected, Platform::Android, true);
+        let ids = options.iter().map(|o| o.id.as_str()).collect::<Vec<_>>();
+        assert_eq!(ids, ["brave", "xyz.unknown", "metamask", "ledger"]);
+        assert_eq!(options[0].action, WalletAction::Injected(Some("com.brave.wallet".to_string())));
+        assert!(options[1].wallet.is_none() && options[1].is_installed());
+        assert_eq!(options[2].action, WalletAction::WalletConnect);
+    }
+
+    #[test]
+    fn test_provider_flags_prefer_specific_wallet() {
+        let injected = InjectedWallets {
+            announced: vec![],
+            flags: vec!["isMetaMask".to_string(), "isBraveWallet".to_string()],
+            available: true,
+        };
+
+        let options = wallet_options(&wallets(), &injected, Platform::Desktop, false);
+        assert_eq!(options[0].id, "brave");
+        assert_eq!(options[0].action, WalletAction::Injected(None));
+        assert_eq!(
+            options[1].action,
+            WalletAction::Install("https://chrome.example/metamask".to_string())
+        );
+        assert_eq!(options.len(), 3);
+    }
+
+    #[test]
+    fn test_wallets_without_link_are_offered_for_installation() {
+        let mut wallets = wallets();
+        wallets[1].desktop_schema = Some("brave://".to_string());
+        wallets[2].app = AppLinks::default();
+
+        let options =
+            wallet_options(&wallets, &InjectedWallets::default(), Platform::Desktop, true);
+        let actions = options.iter().map(|o| (o.id.as_str(), &o.action)).collect::<Vec<_>>();
+        assert_eq!(
+            actions,
+            [
+                ("metamask", &WalletAction::Install("https://chrome.example/metamask".to_string())),
+                ("brave", &WalletAction::WalletConnect),
+                ("ledger", &WalletAction::WalletConnect),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_injected_provider_is_listed() {
+        let injected = InjectedWallets { available: true, ..Default::default() };
+
+        let options = wallet_options(&wallets(), &injected, Platform::Ios, false);
+        assert_eq!(options.len(), 1);
+        assert_eq!(options[0].action, WalletAction::Injected(None));
+    }
+
+    #[test]
+    fn test_recent_wallets_go_first() {
+        let injected = InjectedWallets {
+            announced: vec![info("com.brave.wallet")],
+            flags: vec![],
+            available: true,
+        };
+        let recent = |wallet_type, id: &str, last_used| RecentWallet {
+            wallet_type,
+            id: Some(id.to_string()),
+            name: id.to_string(),
+            wallet: None,
+            last_used,
+        };
+
+        let mut store = RecentWallets::default();
+        store.record(recent(WalletType::Injected, "com.brave.wallet", 100));
+        store.record(recent(WalletType::WalletConnect, "ledger", 200));
+        store.record(recent(WalletType::WalletConnect, "metamask", 300));
+        store.record(recent(WalletType::WalletConnect, "ledger", 400));
+        assert_eq!(store.last().unwrap().id.as_deref(), Some("ledger"));
+        assert_eq!(store.wallets.len(), 3);
+
+        let mut options = wallet_options(&wallets(), &injected, Platform::Desktop, true);
+        prefer_recent(&mut options, &store.wallets);
+        let ids = options.iter().map(|o| o.id.as_str()).collect::<Vec<_>>();
+        assert_eq!(ids, ["ledger", "metamask", "brave"]);
+        assert_eq!(options[0].last_used, Some(400));
+    }
+}
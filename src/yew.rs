@@ -2,7 +2,7 @@ use ethers::{
     providers::Provider,
     types::{Address, Signature},
 };
-use log::{debug, error};
+use log::{debug, error, warn};
 use serde::Serialize;
 use url::Url;
 use yew::{
@@ -10,7 +10,16 @@ use yew::{
     Properties,
 };
 
-use crate::{Ethereum, EthereumBuilder, EthereumError, Event, WalletType};
+use crate::{
+    balance::Balances,
+    chains::Chain,
+    connection::ConnectionState,
+    ens::EnsProfile,
+    prompt::PromptRequest,
+    qr::{self, QrOptions},
+    walletconnect::{open_link, pairing::PairingInfo},
+    Ethereum, EthereumBuilder, EthereumError, Event, WalletType,
+};
 
 #[derive(Clone, PartialEq)]
 pub struct EthereumProviderState {
@@ -37,19 +46,27 @@ pub fn ethereum_context_provider(props: &Props) -> Html {
 #[derive(Clone, Debug)]
 pub struct UseEthereum {
     pub ethereum: UseStateHandle<Ethereum>,
-    pub connected: UseStateHandle<bool>,
     pub accounts: UseStateHandle<Option<Vec<Address>>>,
+    pub active_account: UseStateHandle<Option<Address>>,
+    pub balances: UseStateHandle<Balances>,
     pub chain_id: UseStateHandle<Option<u64>>,
-    pub pairing_url: UseStateHandle<Option<String>>,
+    pub pairing: UseStateHandle<Option<PairingInfo>>,
+    pub connection_state: UseStateHandle<ConnectionState>,
+    pub prompt: UseStateHandle<Option<PromptRequest>>,
+    pub wallet_link: UseStateHandle<Option<String>>,
 }
 
 impl PartialEq for UseEthereum {
     fn eq(&self, other: &Self) -> bool {
         self.ethereum == other.ethereum
-            && self.connected == other.connected
             && self.accounts == other.accounts
+            && self.active_account == other.active_account
+            && self.balances == other.balances
             && self.chain_id == other.chain_id
-            && self.pairing_url == other.pairing_url
+            && self.pairing == other.pairing
+            && self.connection_state == other.connection_state
+            && self.prompt == other.prompt
+            && self.wallet_link == other.wallet_link
     }
 }
 
@@ -92,7 +109,42 @@ impl UseEthereum {
 
     /// Checks if any wallet is currently connected
     pub fn is_connected(&self) -> bool {
-        *self.connected
+        self.connection_state.is_connected()
+    }
+
+    /// Gets current state of the connection
+    pub fn connection_state(&self) -> &ConnectionState {
+        &self.connection_state
+    }
+
+    /// Gets WalletConnect pairing waiting to be approved by the wallet
+    pub fn pairing(&self) -> Option<&PairingInfo> {
+        (*self.pairing)
+            .as_ref()
+            .filter(|_| *self.connection_state == ConnectionState::AwaitingPairing)
+    }
+
+    /// Gets uri of WalletConnect pairing waiting to be approved by the wallet
+    pub fn pairing_url(&self) -> Option<&String> {
+        self.pairing().and_then(|p| p.uri.as_ref())
+    }
+
+    /// Gets wallet request currently waiting for user's approval
+    pub fn prompt(&self) -> Option<&PromptRequest> {
+        (*self.prompt).as_ref()
+    }
+
+    /// Gets link to paired mobile wallet waiting for user's approval of a request, see
+    /// `EthereumBuilder::navigate_to_wallet`
+    pub fn wallet_link(&self) -> Option<&String> {
+        (*self.wallet_link).as_ref()
+    }
+
+    /// Opens paired mobile wallet waiting for user's approval, i.e. on button click
+    pub fn open_wallet(&self) {
+        if let Some(link) = self.wallet_link() {
+            open_link(link);
+        }
     }
 
     /// Checks if injected wallet is available in current context
@@ -110,11 +162,46 @@ impl UseEthereum {
         (*self.chain_id).unwrap_or(0)
     }
 
+    /// Gets description of the chain connected wallet is using
+    pub fn chain(&self) -> Option<&Chain> {
+        (*self.chain_id).and_then(|id| self.ethereum.chains.get(id))
+    }
+
     /// Gets a list of all accounts from connected wallet for chosen (and set) network
     pub fn accounts(&self) -> Option<&Vec<Address>> {
         (*self.accounts).as_ref()
     }
 
+    /// Gets currently selected account of connected wallet
+    pub fn active_account(&self) -> Option<Address> {
+        *self.active_account
+    }
+
+    /// Selects account from the list of connected wallet accounts as the active one
+    pub fn set_active_account(&self, account: Address) {
+        let eth = (*self.ethereum).clone();
+        spawn_local(async move {
+            if let Err(err) = eth.set_active_account(account).await {
+                error!("Cannot select active account {err:?}");
+            }
+        });
+    }
+
+    /// Gets latest known native and watched ERC-20 token balances of the active account
+    pub fn balances(&self) -> &Balances {
+        &self.balances
+    }
+
+    /// Starts tracking balance of ERC-20 token for the active account
+    pub fn watch_token(&self, token: Address) {
+        let eth = (*self.ethereum).clone();
+        spawn_local(async move {
+            if let Err(err) = eth.watch_token(token).await {
+                error!("Cannot watch token balance {err:?}");
+            }
+        });
+    }
+
     /// Signs typed data with the wallet
     pub async fn sign_typed_data<T: Send + Sync + Serialize>(
         &self,
@@ -135,31 +222,43 @@ pub fn use_ethereum() -> UseEthereum {
     if let Some(rpc_url) = std::option_env!("RPC_URL") {
         builder.rpc_node(rpc_url);
     }
-    let connected = use_state(move || false);
+    if let Some(relay_url) = std::option_env!("RELAY_URL") {
+        match Url::parse(relay_url) {
+            Ok(relay_url) => {
+                builder.wc_relay_url(relay_url);
+            }
+            Err(err) => error!("Incorrect relay url in variable RELAY_URL, using default {err:?}"),
+        }
+    }
     let accounts = use_state(move || None as Option<Vec<Address>>);
+    let active_account = use_state(move || None as Option<Address>);
+    let balances = use_state(Balances::default);
     let chain_id = use_state(move || None as Option<u64>);
-    let pairing_url = use_state(move || None as Option<String>);
+    let pairing = use_state(move || None as Option<PairingInfo>);
+    let connection_state = use_state_eq(ConnectionState::default);
+    let prompt = use_state(move || None as Option<PromptRequest>);
+    let wallet_link = use_state(move || None as Option<String>);
 
     let app_url =
         if let Some(app_url) = std::option_env!("APP_URL") { app_url } else { "http://localhost" };
 
     let ethereum = use_state(move || {
         builder
-            .url(
-                Url::parse(app_url).expect(
-                    &format!(
-                        "Correct app url in variable APP_URL is not provided. '{:?}'",
-                        std::option_env!("APP_URL")
-                    ),
-                ),
-            )
+            .url(Url::parse(app_url).expect(&format!(
+                "Correct app url in variable APP_URL is not provided. '{:?}'",
+                std::option_env!("APP_URL")
+            )))
             .build()
     });
 
-    let con = connected.clone();
     let acc = accounts.clone();
+    let active = active_account.clone();
+    let bal = balances.clone();
     let cid = chain_id.clone();
-    let purl = pairing_url.clone();
+    let pair = pairing.clone();
+    let cstate = connection_state.clone();
+    let prm = prompt.clone();
+    let link = wallet_link.clone();
 
     use_effect_with(ethereum.clone(), move |ethereum| {
         if ethereum.has_provider() {
@@ -169,22 +268,38 @@ pub fn use_ethereum() -> UseEthereum {
                 while keep_looping {
                     match eth.next().await {
                         Ok(Some(event)) => match event {
-                            Event::ConnectionWaiting(url) => {
-                                purl.set(Some(url));
-                            }
-                            Event::Connected => {
-                                con.set(true);
-                                purl.set(None)
-                            }
-                            Event::Disconnected => {
-                                con.set(false);
+                            Event::ConnectionWaiting(info) => pair.set(Some(info)),
+                            Event::Connected => pair.set(None),
+                            Event::Disconnected | Event::SessionExpired => {
                                 acc.set(None);
+                                active.set(None);
                                 cid.set(None);
+                                link.set(None);
                                 keep_looping = false;
                             }
-                            Event::Broken => { /* we swallow this event and waiting for restart */ }
+                            Event::Broken(reason) => warn!("Connection broken: {reason}"),
+                            Event::Reconnecting(attempt) => {
+                                debug!("Reconnecting, attempt {attempt}")
+                            }
+                            Event::Reconnected => debug!("Reconnected"),
+                            Event::ConnectionStateChanged(_) => {}
+                            Event::PromptOpened(request) => prm.set(Some(request)),
+                            Event::PromptClosed(_) => {
+                                prm.set(eth.current_prompt());
+                                link.set(None);
+                            }
+                            Event::SessionExpiring(expiry) => {
+                                warn!("WalletConnect session expires at {expiry}")
+                            }
+                            Event::SessionExtended(expiry) => {
+                                debug!("WalletConnect session extended until {expiry}")
+                            }
+                            Event::OpenWallet(wallet) => link.set(Some(wallet)),
                             Event::ChainIdChanged(chain_id) => cid.set(chain_id),
                             Event::AccountsChanged(accounts) => acc.set(accounts),
+                            Event::ActiveAccountChanged(account) => active.set(account),
+                            Event::BalancesChanged(balances) => bal.set(balances),
+                            Event::NewBlock(_) => {}
                         },
                         Ok(None) => {}
                         Err(err) => {
@@ -192,6 +307,9 @@ pub fn use_ethereum() -> UseEthereum {
                             error!("Error on fetching event message {err:?}");
                         }
                     }
+                    // State changes are read from the connection itself, as their events may be
+                    // dropped once the queue is full
+                    cstate.set(eth.connection_state());
                 }
                 debug!("Listener loop ended");
             });
@@ -202,12 +320,84 @@ pub fn use_ethereum() -> UseEthereum {
     yew_hooks::use_effect_once(move || {
         spawn_local(async move {
             let mut e = (*eth).clone();
-            if e.restore().await {
+            let outcome = e.restore().await;
+            debug!("Connection restored {outcome:?}");
+            // Pending WalletConnect pairing is kept even if the session was not restored
+            if e.has_provider() {
                 eth.set(e);
             }
         });
         || {}
     });
 
-    UseEthereum { ethereum, connected, accounts, chain_id, pairing_url }
+    UseEthereum {
+        ethereum,
+        accounts,
+        active_account,
+        balances,
+        chain_id,
+        pairing,
+        connection_state,
+        prompt,
+        wallet_link,
+    }
+}
+
+/// Resolves ENS name and avatar of given address using ethereum from `EthereumContextProvider`
+#[hook]
+pub fn use_ens(address: Option<Address>) -> UseStateHandle<Option<EnsProfile>> {
+    let ethereum = use_context::<UseEthereum>().expect(
+        "No ethereum found. You must wrap your components in an <EthereumContextProvider />",
+    );
+    let profile = use_state(move || None as Option<EnsProfile>);
+
+    let prof = profile.clone();
+    use_effect_with(address, move |address| {
+        prof.set(None);
+        if let Some(address) = *address {
+            let eth = (*ethereum.ethereum).clone();
+            spawn_local(async move {
+                match eth.lookup_ens(address).await {
+                    Ok(p) => prof.set(Some(p)),
+                    Err(err) => error!("ENS lookup failed {err:?}"),
+                }
+            });
+        }
+    });
+
+    profile
+}
+
+#[derive(Properties, PartialEq)]
+pub struct PairingQrProps {
+    #[prop_or_default]
+    pub options: QrOptions,
+    #[prop_or_default]
+    pub class: Classes,
+}
+
+/// QR code of current WalletConnect pairing url. Renders nothing when there is no pairing pending.
+#[function_component(PairingQr)]
+pub fn pairing_qr(props: &PairingQrProps) -> Html {
+    let ethereum = use_context::<UseEthereum>().expect(
+        "No ethereum found. You must wrap your components in an <EthereumContextProvider />",
+    );
+
+    let svg =
+        use_memo((ethereum.pairing_url().cloned(), props.options.clone()), |(url, options)| {
+            url.as_ref().and_then(|url| match qr::to_svg(url, options) {
+                Ok(svg) => Some(svg),
+                Err(err) => {
+                    error!("Could not render pairing QR code {err:?}");
+                    None
+                }
+            })
+        });
+
+    match (*svg).clone() {
+        Some(svg) => html! {
+            <div class={props.class.clone()}>{ Html::from_html_unchecked(svg.into()) }</div>
+        },
+        None => html! {},
+    }
 }
@@ -0,0 +1,96 @@
+//! Runs fake relay together with headless wallet, for driving example apps in a browser.
+//!
+//! Wallet is controlled over HTTP on port next to the relay:
+//! - `POST /pair` with pairing uri as body
+//! - `POST /chain` with chain id as body
+//! - `GET /chain` answering wallet's active chain id
+//! - `POST /disconnect`
+
+use std::{convert::Infallible, env, net::SocketAddr, sync::Arc};
+
+use ethers_web_test_support::{FakeRelay, HeadlessWallet, WalletConfig};
+use hyper::{
+    body::to_bytes,
+    header::{
+        ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN,
+    },
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use log::info;
+
+const DEFAULT_RELAY_ADDR: &str = "127.0.0.1:9545";
+const DEFAULT_CONTROL_ADDR: &str = "127.0.0.1:9546";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let relay_addr = env::var("RELAY_ADDR").unwrap_or(DEFAULT_RELAY_ADDR.to_string());
+    let control_addr: SocketAddr =
+        env::var("CONTROL_ADDR").unwrap_or(DEFAULT_CONTROL_ADDR.to_string()).parse()?;
+
+    let relay = FakeRelay::bind(relay_addr).await?;
+    let mut config = WalletConfig::default();
+    if let Ok(chains) = env::var("CHAINS") {
+        config.chains = chains.split(',').filter_map(|c| c.trim().parse().ok()).collect();
+    }
+    let wallet = Arc::new(HeadlessWallet::connect(&relay.url(), config).await?);
+    let address = wallet.address();
+
+    let service = make_service_fn(move |_| {
+        let wallet = wallet.clone();
+        async move { Ok::<_, Infallible>(service_fn(move |request| handle(request, wallet.clone()))) }
+    });
+    let control = Server::try_bind(&control_addr)?.serve(service);
+
+    println!("Relay url: {}", relay.url());
+    println!("Wallet address: {address:?}");
+    println!("Control: http://{}", control.local_addr());
+    control.await?;
+    Ok(())
+}
+
+async fn handle(
+    request: Request<Body>,
+    wallet: Arc<HeadlessWallet>,
+) -> Result<Response<Body>, Infallible> {
+    let (method, path) = (request.method().clone(), request.uri().path().to_string());
+    info!("Control {method} {path}");
+    let body = match to_bytes(request.into_body()).await {
+        Ok(body) => String::from_utf8_lossy(&body).trim().to_string(),
+        Err(err) => return Ok(response(StatusCode::BAD_REQUEST, err.to_string())),
+    };
+
+    let result = match (&method, path.as_str()) {
+        (&Method::OPTIONS, _) => Ok(String::new()),
+        (&Method::GET, "/chain") => Ok(wallet.chain_id().to_string()),
+        (&Method::POST, "/pair") => wallet.pair(&body).map_err(|e| e.to_string()),
+        (&Method::POST, "/chain") => body
+            .parse()
+            .map_err(|_| format!("Invalid chain id {body}"))
+            .and_then(|chain_id| wallet.change_chain(chain_id).map_err(|e| e.to_string()))
+            .map(|_| String::new()),
+        (&Method::POST, "/disconnect") => {
+            wallet.disconnect().map(|_| String::new()).map_err(|e| e.to_string())
+        }
+        _ => Err(format!("Unknown endpoint {method} {path}")),
+    };
+
+    Ok(match result {
+        Ok(body) => response(StatusCode::OK, body),
+        Err(err) => response(StatusCode::BAD_REQUEST, err),
+    })
+}
+
+/// Response allowing calls from example apps served on other origins
+fn response(status: StatusCode, body: String) -> Response<Body> {
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = status;
+    let headers = response.headers_mut();
+    headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, "*".parse().expect("Valid header value"));
+    headers.insert(
+        ACCESS_CONTROL_ALLOW_METHODS,
+        "GET, POST, OPTIONS".parse().expect("Valid header value"),
+    );
+    headers.insert(ACCESS_CONTROL_ALLOW_HEADERS, "*".parse().expect("Valid header value"));
+    response
+}
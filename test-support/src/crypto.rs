@@ -0,0 +1,128 @@
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use data_encoding::BASE64;
+use hkdf::Hkdf;
+use rand::{rngs::OsRng, RngCore};
+use sha2::{Digest, Sha256};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::Error;
+
+const IV_LENGTH: usize = 12;
+const KEY_LENGTH: usize = 32;
+/// Envelope encrypted with a key known to both sides
+const TYPE_0: u8 = 0;
+/// Envelope carrying sender's public key, used before the key is agreed
+const TYPE_1: u8 = 1;
+
+/// Symmetric key of a pairing or session
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SymKey(pub [u8; KEY_LENGTH]);
+
+impl SymKey {
+    pub fn random() -> Self {
+        let mut key = [0u8; KEY_LENGTH];
+        OsRng.fill_bytes(&mut key);
+        Self(key)
+    }
+
+    pub fn from_hex(key: &str) -> Result<Self, Error> {
+        let bytes = hex::decode(key).map_err(|_| Error::InvalidKey)?;
+        Ok(Self(bytes.try_into().map_err(|_| Error::InvalidKey)?))
+    }
+
+    /// Derives session key out of Diffie-Hellman key agreement
+    pub fn derive(secret: &StaticSecret, peer: &PublicKey) -> Self {
+        let shared = secret.diffie_hellman(peer);
+        let mut key = [0u8; KEY_LENGTH];
+        Hkdf::<Sha256>::new(None, shared.as_bytes())
+            .expand(&[], &mut key)
+            .expect("Key length is valid for SHA-256");
+        Self(key)
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+
+    /// Relay topic messages encrypted with this key are published on
+    pub fn topic(&self) -> String {
+        hex::encode(Sha256::digest(self.0))
+    }
+
+    /// Encrypts message into base64 encoded type 0 envelope
+    pub fn encrypt(&self, message: &str) -> Result<String, Error> {
+        let mut iv = [0u8; IV_LENGTH];
+        OsRng.fill_bytes(&mut iv);
+
+        let sealed = self
+            .cipher()
+            .encrypt(&Nonce::from(iv), message.as_bytes())
+            .map_err(|_| Error::Encryption)?;
+        Ok(BASE64.encode(&[&[TYPE_0][..], &iv, &sealed].concat()))
+    }
+
+    /// Decrypts base64 encoded envelope of type 0 or 1
+    pub fn decrypt(&self, envelope: &str) -> Result<String, Error> {
+        let bytes = BASE64.decode(envelope.as_bytes()).map_err(|_| Error::InvalidEnvelope)?;
+        let sealed = match bytes.first() {
+            Some(&TYPE_0) => &bytes[1..],
+            Some(&TYPE_1) if bytes.len() > 1 + KEY_LENGTH => &bytes[1 + KEY_LENGTH..],
+            _ => return Err(Error::InvalidEnvelope),
+        };
+        if sealed.len() < IV_LENGTH {
+            return Err(Error::InvalidEnvelope);
+        }
+
+        let (iv, sealed) = sealed.split_at(IV_LENGTH);
+        let iv: [u8; IV_LENGTH] = iv.try_into().map_err(|_| Error::InvalidEnvelope)?;
+        let message =
+            self.cipher().decrypt(&Nonce::from(iv), sealed).map_err(|_| Error::Encryption)?;
+        String::from_utf8(message).map_err(|_| Error::InvalidEnvelope)
+    }
+
+    fn cipher(&self) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::new(&Key::from(self.0))
+    }
+}
+
+/// Generates new X25519 key pair
+pub fn key_pair() -> (StaticSecret, PublicKey) {
+    let secret = StaticSecret::random_from_rng(OsRng);
+    let public = PublicKey::from(&secret);
+    (secret, public)
+}
+
+pub fn public_key_from_hex(key: &str) -> Result<PublicKey, Error> {
+    let bytes: [u8; KEY_LENGTH] = hex::decode(key)
+        .map_err(|_| Error::InvalidKey)?
+        .try_into()
+        .map_err(|_| Error::InvalidKey)?;
+    Ok(PublicKey::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope_roundtrip() {
+        let key = SymKey::random();
+        let envelope = key.encrypt(r#"{"id":1}"#).unwrap();
+
+        assert_eq!(key.decrypt(&envelope).unwrap(), r#"{"id":1}"#);
+        assert!(SymKey::random().decrypt(&envelope).is_err());
+    }
+
+    #[test]
+    fn test_both_sides_derive_the_same_key() {
+        let (dapp_secret, dapp_public) = key_pair();
+        let (wallet_secret, wallet_public) = key_pair();
+
+        let dapp_key = SymKey::derive(&dapp_secret, &wallet_public);
+        assert_eq!(dapp_key, SymKey::derive(&wallet_secret, &dapp_public));
+        assert_eq!(dapp_key.topic().len(), 64);
+    }
+}
@@ -0,0 +1,255 @@
+use std::collections::{HashMap, VecDeque};
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::{
+    crypto::{key_pair, public_key_from_hex, SymKey},
+    rpc::{publish, subscribe, tags, Payload, Request, Response, SubscriptionData},
+    Error,
+};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// dApp side of the Sign API, proposing sessions to [`crate::HeadlessWallet`] and sending
+/// requests through the relay
+pub struct DappPeer {
+    socket: Socket,
+    keys: HashMap<String, SymKey>,
+    /// Key pair of the pending proposal and its pairing topic
+    proposal: Option<(String, StaticSecret, PublicKey)>,
+    session: Option<String>,
+    /// Messages received while waiting for other ones
+    received: VecDeque<(String, Payload)>,
+}
+
+impl DappPeer {
+    pub async fn connect(relay_url: &str) -> Result<Self, Error> {
+        let (socket, _) = connect_async(relay_url).await?;
+        Ok(Self {
+            socket,
+            keys: HashMap::new(),
+            proposal: None,
+            session: None,
+            received: VecDeque::new(),
+        })
+    }
+
+    /// Reconnects to the relay with session known from previous connection, as if it was
+    /// restored from storage
+    pub async fn resume(relay_url: &str, session: SymKey) -> Result<Self, Error> {
+        let mut dapp = Self::connect(relay_url).await?;
+        dapp.subscribe(session).await?;
+        dapp.session = Some(session.topic());
+        Ok(dapp)
+    }
+
+    /// Key of the settled session
+    pub fn session_key(&self) -> Option<SymKey> {
+        self.keys.get(self.session.as_ref()?).copied()
+    }
+
+    /// Topic of the settled session
+    pub fn session(&self) -> Option<String> {
+        self.session.clone()
+    }
+
+    /// Publishes session proposal with given namespaces on new pairing. Returns pairing uri to
+    /// be passed to the wallet.
+    pub async fn propose(
+        &mut self,
+        required_namespaces: Value,
+        optional_namespaces: Value,
+    ) -> Result<String, Error> {
+        let (topic, pairing, secret, public) = self.pair().await?;
+        let params = json!({
+            "relays": [{ "protocol": "irn" }],
+            "proposer": { "publicKey": hex::encode(public.as_bytes()), "metadata": metadata() },
+            "requiredNamespaces": required_namespaces,
+            "optionalNamespaces": optional_namespaces,
+        });
+        let request = Request::new("wc_sessionPropose", params);
+        self.publish(&topic, &request, tags::SESSION_PROPOSE).await?;
+        self.proposal = Some((topic.clone(), secret, public));
+        Ok(uri(&topic, &pairing))
+    }
+
+    /// Waits for the wallet to approve the proposal and settle the session. Returns settle
+    /// params.
+    pub async fn settle(&mut self) -> Result<Value, Error> {
+        let (topic, secret, _) = self.proposal.take().ok_or(Error::ConnectionClosed)?;
+        let response = self.response_on(&topic).await?;
+        let responder = response.result.as_ref().and_then(|r| r["responderPublicKey"].as_str());
+        let responder = public_key_from_hex(responder.ok_or(Error::SessionRejected)?)?;
+
+        let session = SymKey::derive(&secret, &responder);
+        self.subscribe(session).await?;
+        let settle = loop {
+            let (topic, payload) = self.receive().await?;
+            match payload {
+                Payload::Request(request) if request.method == "wc_sessionSettle" => {
+                    self.respond(&topic, &request, json!(true)).await?;
+                    break request.params;
+                }
+                payload => self.received.push_back((topic, payload)),
+            }
+        };
+        self.session = Some(session.topic());
+        Ok(settle)
+    }
+
+    /// Sends `wc_sessionRequest` on the session and waits for the wallet's answer
+    pub async fn request(
+        &mut self,
+        chain_id: u64,
+        method: &str,
+        params: Value,
+    ) -> Result<Response, Error> {
+        let params = json!({
+            "request": { "method": method, "params": params },
+            "chainId": format!("eip155:{chain_id}"),
+        });
+        self.call("wc_sessionRequest", params).await
+    }
+
+    /// Sends Sign API request on the session, i.e. `wc_sessionPing`, and waits for the answer
+    pub async fn call(&mut self, method: &str, params: Value) -> Result<Response, Error> {
+        let topic = self.session.clone().ok_or(Error::ConnectionClosed)?;
+        let request = Request::new(method, params);
+        self.publish(&topic, &request, tags::of(method).unwrap_or_default()).await?;
+        loop {
+            match self.response_on(&topic).await? {
+                response if response.id == request.id => return Ok(response),
+                _ => continue,
+            }
+        }
+    }
+
+    /// Deletes the session on dApp's side
+    pub async fn disconnect(&mut self) -> Result<Response, Error> {
+        let params = json!({ "code": 6000, "message": "User disconnected." });
+        let response = self.call("wc_sessionDelete", params).await?;
+        self.session = None;
+        Ok(response)
+    }
+
+    /// Waits for next request the wallet sent on the session, i.e. `wc_sessionEvent`
+    pub async fn next_request(&mut self) -> Result<Request, Error> {
+        let topic = self.session.clone().ok_or(Error::ConnectionClosed)?;
+        if let Some(index) =
+            self.received.iter().position(|(t, p)| t == &topic && matches!(p, Payload::Request(_)))
+        {
+            if let Some((_, Payload::Request(request))) = self.received.remove(index) {
+                self.respond(&topic, &request, json!(true)).await?;
+                return Ok(request);
+            }
+        }
+
+        loop {
+            match self.receive().await? {
+                (t, Payload::Request(request)) if t == topic => {
+                    self.respond(&t, &request, json!(true)).await?;
+                    return Ok(request);
+                }
+                received => self.received.push_back(received),
+            }
+        }
+    }
+
+    /// Subscribes to pairing topic of new random key
+    async fn pair(&mut self) -> Result<(String, SymKey, StaticSecret, PublicKey), Error> {
+        let pairing = SymKey::random();
+        let topic = SymKey::random().to_hex();
+        self.keys.insert(topic.clone(), pairing);
+        self.send(&subscribe(&topic)).await?;
+        let (secret, public) = key_pair();
+        Ok((topic, pairing, secret, public))
+    }
+
+    async fn subscribe(&mut self, key: SymKey) -> Result<(), Error> {
+        let topic = key.topic();
+        self.keys.insert(topic.clone(), key);
+        self.send(&subscribe(&topic)).await
+    }
+
+    async fn send(&mut self, payload: &impl serde::Serialize) -> Result<(), Error> {
+        let text = serde_json::to_string(payload)?;
+        Ok(self.socket.send(Message::Text(text)).await?)
+    }
+
+    async fn publish(
+        &mut self,
+        topic: &str,
+        payload: &impl serde::Serialize,
+        tag: u32,
+    ) -> Result<(), Error> {
+        let key = self.keys.get(topic).ok_or_else(|| Error::UnknownTopic(topic.to_string()))?;
+        let message = key.encrypt(&serde_json::to_string(payload)?)?;
+        self.send(&publish(topic, message, tag)).await
+    }
+
+    async fn respond(
+        &mut self,
+        topic: &str,
+        request: &Request,
+        result: Value,
+    ) -> Result<(), Error> {
+        let tag = tags::of(&request.method).map(tags::response).unwrap_or_default();
+        self.publish(topic, &Response::result(request.id, result), tag).await
+    }
+
+    /// Waits for next response published on given topic
+    async fn response_on(&mut self, topic: &str) -> Result<Response, Error> {
+        if let Some(index) =
+            self.received.iter().position(|(t, p)| t == topic && matches!(p, Payload::Response(_)))
+        {
+            if let Some((_, Payload::Response(response))) = self.received.remove(index) {
+                return Ok(response);
+            }
+        }
+
+        loop {
+            match self.receive().await? {
+                (t, Payload::Response(response)) if t == topic => return Ok(response),
+                received => self.received.push_back(received),
+            }
+        }
+    }
+
+    /// Waits for next message published on any subscribed topic
+    async fn receive(&mut self) -> Result<(String, Payload), Error> {
+        loop {
+            let message = self.socket.next().await.ok_or(Error::ConnectionClosed)??;
+            let Message::Text(text) = message else { continue };
+            let Ok(Payload::Request(request)) = serde_json::from_str(&text) else { continue };
+            if request.method != "irn_subscription" {
+                continue;
+            }
+
+            self.send(&Response::result(request.id, json!(true))).await?;
+            let data: SubscriptionData = serde_json::from_value(request.params["data"].clone())?;
+            let key = self
+                .keys
+                .get(&data.topic)
+                .ok_or_else(|| Error::UnknownTopic(data.topic.clone()))?;
+            let payload = serde_json::from_str(&key.decrypt(&data.message)?)?;
+            return Ok((data.topic, payload));
+        }
+    }
+}
+
+fn uri(topic: &str, key: &SymKey) -> String {
+    format!("wc:{topic}@2?relay-protocol=irn&symKey={}", key.to_hex())
+}
+
+fn metadata() -> Value {
+    json!({
+        "name": "Test dApp",
+        "description": "dApp peer for integration tests",
+        "url": "http://localhost",
+        "icons": [],
+    })
+}
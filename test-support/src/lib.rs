@@ -0,0 +1,58 @@
+//! Local stand-ins for WalletConnect infrastructure, letting `ethers-web` WalletConnect flows be
+//! exercised without internet access.
+//!
+//! [`FakeRelay`] speaks the relay's `irn_*` JSON-RPC over WebSocket and [`HeadlessWallet`] is
+//! a scripted wallet peer, approving sessions and answering signing requests with a fixed key.
+//! [`DappPeer`] plays the dApp side of the conversation.
+
+pub mod crypto;
+pub mod dapp;
+pub mod relay;
+pub mod rpc;
+pub mod wallet;
+
+pub use dapp::DappPeer;
+pub use relay::FakeRelay;
+pub use wallet::{HeadlessWallet, WalletConfig, WalletEvent};
+
+use thiserror::Error;
+
+/// Error struct
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Invalid key")]
+    InvalidKey,
+
+    #[error("Invalid message envelope")]
+    InvalidEnvelope,
+
+    #[error("Invalid pairing uri {0}")]
+    InvalidUri(String),
+
+    #[error("Unknown topic {0}")]
+    UnknownTopic(String),
+
+    #[error("Session was rejected")]
+    SessionRejected,
+
+    #[error("Relay connection closed")]
+    ConnectionClosed,
+
+    #[error("Encryption failed")]
+    Encryption,
+
+    #[error(transparent)]
+    WebSocket(#[from] Box<tokio_tungstenite::tungstenite::Error>),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Signer(#[from] ethers::signers::WalletError),
+}
+
+impl From<tokio_tungstenite::tungstenite::Error> for Error {
+    fn from(err: tokio_tungstenite::tungstenite::Error) -> Self {
+        Self::WebSocket(Box::new(err))
+    }
+}
@@ -0,0 +1,310 @@
+use std::{
+    collections::HashMap,
+    io,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use futures_util::{SinkExt, StreamExt};
+use log::{debug, error};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::{
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    sync::mpsc::{unbounded_channel, UnboundedSender},
+    task::JoinHandle,
+};
+use tokio_tungstenite::{accept_async, tungstenite::Message};
+
+use crate::rpc::{Payload, PublishParams, Request, Response, SubscriptionData};
+
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// WebSocket server speaking WalletConnect relay JSON-RPC. Messages published to a topic are
+/// delivered to all other subscribers of that topic, or kept until someone subscribes.
+pub struct FakeRelay {
+    addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl FakeRelay {
+    /// Starts relay on random local port
+    pub async fn start() -> io::Result<Self> {
+        Self::bind("127.0.0.1:0").await
+    }
+
+    pub async fn bind(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(RelayState::default()));
+
+        let task = tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        tokio::spawn(handle_connection(stream, state.clone()));
+                    }
+                    Err(err) => error!("Relay connection not accepted {err:?}"),
+                }
+            }
+        });
+
+        Ok(Self { addr, task })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Url to be passed as relay url to WalletConnect clients
+    pub fn url(&self) -> String {
+        format!("ws://{}", self.addr)
+    }
+}
+
+impl Drop for FakeRelay {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+struct Subscription {
+    id: String,
+    connection: u64,
+}
+
+#[derive(Default)]
+struct RelayState {
+    next_connection: u64,
+    next_subscription: u64,
+    connections: HashMap<u64, UnboundedSender<Message>>,
+    subscriptions: HashMap<String, Vec<Subscription>>,
+    mailbox: HashMap<String, Vec<SubscriptionData>>,
+}
+
+#[derive(Deserialize)]
+struct TopicParams {
+    topic: String,
+}
+
+#[derive(Deserialize)]
+struct TopicsParams {
+    topics: Vec<String>,
+}
+
+impl RelayState {
+    fn connect(&mut self, sender: UnboundedSender<Message>) -> u64 {
+        self.next_connection += 1;
+        self.connections.insert(self.next_connection, sender);
+        self.next_connection
+    }
+
+    fn disconnect(&mut self, connection: u64) {
+        self.connections.remove(&connection);
+        for subscriptions in self.subscriptions.values_mut() {
+            subscriptions.retain(|s| s.connection != connection);
+        }
+    }
+
+    fn send(&self, connection: u64, payload: &impl serde::Serialize) {
+        if let (Some(sender), Ok(text)) =
+            (self.connections.get(&connection), serde_json::to_string(payload))
+        {
+            _ = sender.send(Message::Text(text));
+        }
+    }
+
+    fn handle(&mut self, connection: u64, request: Request) {
+        debug!("Relay request {} {}", request.method, request.params);
+        let mut subscribed = vec![];
+        let result = match request.method.as_str() {
+            "irn_subscribe" => parse::<TopicParams>(request.params).map(|p| {
+                subscribed.push(p.topic.clone());
+                json!(self.subscribe(connection, p.topic))
+            }),
+            "irn_batchSubscribe" => parse::<TopicsParams>(request.params).map(|p| {
+                subscribed.extend(p.topics.clone());
+                let ids = p.topics.into_iter().map(|t| self.subscribe(connection, t));
+                json!(ids.collect::<Vec<_>>())
+            }),
+            "irn_unsubscribe" => parse::<TopicParams>(request.params).map(|p| {
+                self.unsubscribe(connection, &p.topic);
+                json!(true)
+            }),
+            "irn_batchUnsubscribe" => parse::<Value>(request.params).map(|p| {
+                let subscriptions = p["subscriptions"].as_array().cloned().unwrap_or_default();
+                for topic in subscriptions.iter().filter_map(|s| s["topic"].as_str()) {
+                    self.unsubscribe(connection, topic);
+                }
+                json!(true)
+            }),
+            "irn_publish" => parse::<PublishParams>(request.params).map(|p| {
+                self.publish(connection, p);
+                json!(true)
+            }),
+            "irn_fetchMessages" => parse::<TopicParams>(request.params).map(|p| {
+                json!({ "messages": self.mailbox.remove(&p.topic).unwrap_or_default(), "hasMore": false })
+            }),
+            "irn_batchFetchMessages" => parse::<TopicsParams>(request.params).map(|p| {
+                let messages = p.topics.iter().flat_map(|t| self.mailbox.remove(t).unwrap_or_default());
+                json!({ "messages": messages.collect::<Vec<_>>(), "hasMore": false })
+            }),
+            _ => {
+                let response = Response::error(request.id, METHOD_NOT_FOUND, "Method not found");
+                return self.send(connection, &response);
+            }
+        };
+
+        let response = match result {
+            Ok(result) => Response::result(request.id, result),
+            Err(err) => Response::error(request.id, INVALID_PARAMS, &err.to_string()),
+        };
+        self.send(connection, &response);
+        self.deliver_mailbox(connection, &subscribed);
+    }
+
+    fn subscribe(&mut self, connection: u64, topic: String) -> String {
+        self.next_subscription += 1;
+        let id = format!("{:064x}", self.next_subscription);
+        let subscriptions = self.subscriptions.entry(topic).or_default();
+        subscriptions.retain(|s| s.connection != connection);
+        subscriptions.push(Subscription { id: id.clone(), connection });
+        id
+    }
+
+    fn unsubscribe(&mut self, connection: u64, topic: &str) {
+        if let Some(subscriptions) = self.subscriptions.get_mut(topic) {
+            subscriptions.retain(|s| s.connection != connection);
+        }
+    }
+
+    fn publish(&mut self, connection: u64, params: PublishParams) {
+        let published_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let data = SubscriptionData {
+            topic: params.topic,
+            message: params.message,
+            published_at: published_at.as_millis() as u64,
+            tag: params.tag,
+        };
+
+        if !self.deliver(&data, connection) {
+            self.mailbox.entry(data.topic.clone()).or_default().push(data);
+        }
+    }
+
+    /// Delivers message to subscribers other than the publisher. Returns `false` if there was
+    /// nobody to deliver to.
+    fn deliver(&self, data: &SubscriptionData, publisher: u64) -> bool {
+        let subscriptions =
+            self.subscriptions.get(&data.topic).map(Vec::as_slice).unwrap_or_default();
+        let mut delivered = false;
+        for subscription in subscriptions.iter().filter(|s| s.connection != publisher) {
+            self.deliver_to(subscription, data);
+            delivered = true;
+        }
+        delivered
+    }
+
+    fn deliver_to(&self, subscription: &Subscription, data: &SubscriptionData) {
+        let params = json!({ "id": subscription.id, "data": data });
+        self.send(subscription.connection, &Request::new("irn_subscription", params));
+    }
+
+    /// Delivers messages kept for newly subscribed topics
+    fn deliver_mailbox(&mut self, connection: u64, topics: &[String]) {
+        for topic in topics {
+            let subscription = self
+                .subscriptions
+                .get(topic)
+                .and_then(|s| s.iter().find(|s| s.connection == connection));
+            let Some(subscription) = subscription else { continue };
+            for data in self.mailbox.remove(topic).unwrap_or_default() {
+                self.deliver_to(subscription, &data);
+            }
+        }
+    }
+}
+
+fn parse<T: serde::de::DeserializeOwned>(params: Value) -> Result<T, serde_json::Error> {
+    serde_json::from_value(params)
+}
+
+async fn handle_connection(stream: TcpStream, state: Arc<Mutex<RelayState>>) {
+    let ws = match accept_async(stream).await {
+        Ok(ws) => ws,
+        Err(err) => return error!("WebSocket handshake failed {err:?}"),
+    };
+    let (mut sink, mut stream) = ws.split();
+
+    let (sender, mut receiver) = unbounded_channel();
+    let connection = state.lock().unwrap().connect(sender);
+    let writer = tokio::spawn(async move {
+        while let Some(message) = receiver.recv().await {
+            if sink.send(message).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    while let Some(Ok(message)) = stream.next().await {
+        match message {
+            Message::Text(text) => match serde_json::from_str::<Payload>(&text) {
+                Ok(Payload::Request(request)) => state.lock().unwrap().handle(connection, request),
+                // Acknowledgements of delivered messages
+                Ok(Payload::Response(_)) => {}
+                Err(err) => error!("Unknown relay message {text} {err:?}"),
+            },
+            Message::Close(_) => break,
+            _ => {}
+        }
+    }
+
+    state.lock().unwrap().disconnect(connection);
+    writer.abort();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::{publish, subscribe};
+    use tokio_tungstenite::connect_async;
+
+    async fn next_payload<S>(stream: &mut S) -> Payload
+    where
+        S: StreamExt<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+    {
+        loop {
+            if let Message::Text(text) = stream.next().await.unwrap().unwrap() {
+                return serde_json::from_str(&text).unwrap();
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_messages_are_kept_until_subscription() {
+        let relay = FakeRelay::start().await.unwrap();
+        let (mut publisher, _) = connect_async(relay.url()).await.unwrap();
+        let (mut subscriber, _) = connect_async(relay.url()).await.unwrap();
+
+        let request = publish("topic", "message".to_string(), 1100);
+        publisher.send(Message::Text(serde_json::to_string(&request).unwrap())).await.unwrap();
+        assert!(matches!(next_payload(&mut publisher).await, Payload::Response(_)));
+
+        let request = subscribe("topic");
+        subscriber.send(Message::Text(serde_json::to_string(&request).unwrap())).await.unwrap();
+        assert!(matches!(next_payload(&mut subscriber).await, Payload::Response(_)));
+
+        let Payload::Request(delivered) = next_payload(&mut subscriber).await else {
+            panic!("Message was not delivered");
+        };
+        assert_eq!(delivered.method, "irn_subscription");
+        let data: SubscriptionData =
+            serde_json::from_value(delivered.params["data"].clone()).unwrap();
+        assert_eq!(
+            (data.topic.as_str(), data.message.as_str(), data.tag),
+            ("topic", "message", 1100)
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+pub const JSONRPC_VERSION: &str = "2.0";
+
+/// Relay tags of Sign API messages
+pub mod tags {
+    pub const PAIRING_DELETE: u32 = 1000;
+    pub const PAIRING_PING: u32 = 1002;
+    pub const SESSION_PROPOSE: u32 = 1100;
+    pub const SESSION_SETTLE: u32 = 1102;
+    pub const SESSION_UPDATE: u32 = 1104;
+    pub const SESSION_EXTEND: u32 = 1106;
+    pub const SESSION_REQUEST: u32 = 1108;
+    pub const SESSION_EVENT: u32 = 1110;
+    pub const SESSION_DELETE: u32 = 1112;
+    pub const SESSION_PING: u32 = 1114;
+    pub const SESSION_AUTHENTICATE: u32 = 1116;
+
+    /// Tag of the response to request of given tag
+    pub fn response(tag: u32) -> u32 {
+        tag + 1
+    }
+
+    /// Tag of Sign API request method
+    pub fn of(method: &str) -> Option<u32> {
+        Some(match method {
+            "wc_pairingDelete" => PAIRING_DELETE,
+            "wc_pairingPing" => PAIRING_PING,
+            "wc_sessionPropose" => SESSION_PROPOSE,
+            "wc_sessionSettle" => SESSION_SETTLE,
+            "wc_sessionUpdate" => SESSION_UPDATE,
+            "wc_sessionExtend" => SESSION_EXTEND,
+            "wc_sessionRequest" => SESSION_REQUEST,
+            "wc_sessionEvent" => SESSION_EVENT,
+            "wc_sessionDelete" => SESSION_DELETE,
+            "wc_sessionPing" => SESSION_PING,
+            "wc_sessionAuthenticate" => SESSION_AUTHENTICATE,
+            _ => return None,
+        })
+    }
+}
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Returns unique JSON-RPC request id
+pub fn next_id() -> u64 {
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Request {
+    pub id: u64,
+    pub jsonrpc: String,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+impl Request {
+    pub fn new(method: &str, params: Value) -> Self {
+        Self {
+            id: next_id(),
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            method: method.to_string(),
+            params,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ErrorObject {
+    pub code: i64,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Response {
+    pub id: u64,
+    pub jsonrpc: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorObject>,
+}
+
+impl Response {
+    pub fn result(id: u64, result: Value) -> Self {
+        Self { id, jsonrpc: JSONRPC_VERSION.to_string(), result: Some(result), error: None }
+    }
+
+    pub fn error(id: u64, code: i64, message: &str) -> Self {
+        Self {
+            id,
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            result: None,
+            error: Some(ErrorObject { code, message: message.to_string() }),
+        }
+    }
+}
+
+/// Any JSON-RPC message
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Payload {
+    Request(Request),
+    Response(Response),
+}
+
+/// Parameters of `irn_publish`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PublishParams {
+    pub topic: String,
+    pub message: String,
+    pub ttl: u64,
+    pub tag: u32,
+    #[serde(default)]
+    pub prompt: bool,
+}
+
+/// Message delivered to subscribers with `irn_subscription`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionData {
+    pub topic: String,
+    pub message: String,
+    pub published_at: u64,
+    pub tag: u32,
+}
+
+/// Builds `irn_publish` request
+pub fn publish(topic: &str, message: String, tag: u32) -> Request {
+    let params = PublishParams { topic: topic.to_string(), message, ttl: 300, tag, prompt: false };
+    Request::new("irn_publish", json!(params))
+}
+
+/// Builds `irn_subscribe` request
+pub fn subscribe(topic: &str) -> Request {
+    Request::new("irn_subscribe", json!({ "topic": topic }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_kinds() {
+        let request = r#"{"id":1,"jsonrpc":"2.0","method":"irn_subscribe","params":{"topic":"a"}}"#;
+        let response = r#"{"id":1,"jsonrpc":"2.0","result":true}"#;
+
+        assert!(matches!(serde_json::from_str(request).unwrap(), Payload::Request(_)));
+        assert!(matches!(serde_json::from_str(response).unwrap(), Payload::Response(_)));
+        assert_eq!(tags::of("wc_sessionRequest").map(tags::response), Some(1109));
+    }
+}
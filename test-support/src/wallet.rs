@@ -0,0 +1,531 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use ethers::{
+    signers::{LocalWallet, Signer},
+    types::{
+        transaction::{eip2718::TypedTransaction, eip712::TypedData},
+        Address, Bytes, TransactionRequest, H256, U256,
+    },
+    utils::keccak256,
+};
+use futures_util::{SinkExt, StreamExt};
+use log::{debug, error};
+use serde_json::{json, Value};
+use tokio::{
+    sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+    task::JoinHandle,
+};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use url::Url;
+
+use crate::{
+    crypto::{key_pair, public_key_from_hex, SymKey},
+    rpc::{publish, subscribe, tags, Payload, Request, Response, SubscriptionData},
+    Error,
+};
+
+/// Anvil's first development account
+pub const DEFAULT_PRIVATE_KEY: &str =
+    "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+/// Lifetime of settled sessions
+pub const SESSION_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+
+const INVALID_EXTEND_REQUEST: i64 = 1004;
+const USER_REJECTED: i64 = 5000;
+const UNSUPPORTED_METHOD: i64 = 10001;
+const UNRECOGNIZED_CHAIN: i64 = 4902;
+
+/// Methods the wallet approves in its sessions
+pub const METHODS: [&str; 5] = [
+    "personal_sign",
+    "eth_sign",
+    "eth_signTypedData_v4",
+    "eth_sendTransaction",
+    "wallet_switchEthereumChain",
+];
+
+/// Behaviour of [`HeadlessWallet`]
+#[derive(Clone, Debug)]
+pub struct WalletConfig {
+    pub signer: LocalWallet,
+    /// Supported chains, first one is active after the session is settled
+    pub chains: Vec<u64>,
+    /// Rejects session proposals if unset
+    pub approve_sessions: bool,
+    pub name: String,
+}
+
+impl Default for WalletConfig {
+    fn default() -> Self {
+        Self {
+            signer: DEFAULT_PRIVATE_KEY.parse().expect("Default key is valid"),
+            chains: vec![1],
+            approve_sessions: true,
+            name: "Headless Wallet".to_string(),
+        }
+    }
+}
+
+/// Observable steps of the wallet's conversation with the dApp
+#[derive(Clone, Debug, PartialEq)]
+pub enum WalletEvent {
+    Paired(String),
+    SessionProposed(String),
+    SessionRejected(String),
+    SessionSettled(String),
+    /// Session or pairing of given topic was pinged
+    Pinged(String),
+    /// Session was extended until given unix timestamp
+    SessionExtended(u64),
+    /// Session request with method name and its params
+    Request(String, Value),
+    SessionDeleted(String),
+}
+
+struct WalletState {
+    config: WalletConfig,
+    chain_id: u64,
+    keys: HashMap<String, SymKey>,
+    session: Option<String>,
+    /// Unix timestamp of session's expiry
+    expiry: Option<u64>,
+    outgoing: UnboundedSender<Message>,
+    events: UnboundedSender<WalletEvent>,
+}
+
+type Shared = Arc<Mutex<WalletState>>;
+
+/// Wallet peer connected to the relay, approving proposals and signing requests without user
+/// interaction
+pub struct HeadlessWallet {
+    state: Shared,
+    events: tokio::sync::Mutex<UnboundedReceiver<WalletEvent>>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl HeadlessWallet {
+    pub async fn connect(relay_url: &str, config: WalletConfig) -> Result<Self, Error> {
+        let (ws, _) = connect_async(relay_url).await?;
+        let (mut sink, mut stream) = ws.split();
+
+        let (outgoing, mut receiver) = unbounded_channel::<Message>();
+        let (events, events_receiver) = unbounded_channel();
+        let state = Arc::new(Mutex::new(WalletState {
+            chain_id: config.chains.first().copied().unwrap_or(1),
+            config,
+            keys: HashMap::new(),
+            session: None,
+            expiry: None,
+            outgoing,
+            events,
+        }));
+
+        let writer = tokio::spawn(async move {
+            while let Some(message) = receiver.recv().await {
+                if sink.send(message).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let reader_state = state.clone();
+        let reader = tokio::spawn(async move {
+            while let Some(Ok(message)) = stream.next().await {
+                let Message::Text(text) = message else { continue };
+                match serde_json::from_str::<Payload>(&text) {
+                    Ok(Payload::Request(request)) if request.method == "irn_subscription" => {
+                        let ack = Response::result(request.id, json!(true));
+                        reader_state.lock().unwrap().send(&ack);
+                        let data = serde_json::from_value(request.params["data"].clone());
+                        match data {
+                            Ok(data) => {
+                                if let Err(err) = handle_message(&reader_state, data).await {
+                                    error!("Wallet could not handle message {err:?}");
+                                }
+                            }
+                            Err(err) => error!("Invalid subscription data {err:?}"),
+                        }
+                    }
+                    Ok(payload) => debug!("Wallet received {payload:?}"),
+                    Err(err) => error!("Unknown relay message {text} {err:?}"),
+                }
+            }
+        });
+
+        Ok(Self {
+            state,
+            events: tokio::sync::Mutex::new(events_receiver),
+            tasks: vec![writer, reader],
+        })
+    }
+
+    pub fn address(&self) -> Address {
+        self.state.lock().unwrap().config.signer.address()
+    }
+
+    pub fn chain_id(&self) -> u64 {
+        self.state.lock().unwrap().chain_id
+    }
+
+    /// Topic of the settled session
+    pub fn session(&self) -> Option<String> {
+        self.state.lock().unwrap().session.clone()
+    }
+
+    /// Unix timestamp of the session's expiry
+    pub fn expiry(&self) -> Option<u64> {
+        self.state.lock().unwrap().expiry
+    }
+
+    /// Pairs with the dApp using `wc:` uri, as if it was scanned from QR code
+    pub fn pair(&self, uri: &str) -> Result<String, Error> {
+        let invalid = || Error::InvalidUri(uri.to_string());
+        let url = Url::parse(uri).map_err(|_| invalid())?;
+        let (topic, _) = url.path().split_once('@').ok_or_else(invalid)?;
+        let key = url.query_pairs().find(|(k, _)| k == "symKey").ok_or_else(invalid)?.1;
+
+        let mut state = self.state.lock().unwrap();
+        state.keys.insert(topic.to_string(), SymKey::from_hex(&key)?);
+        state.send(&subscribe(topic));
+        state.emit(WalletEvent::Paired(topic.to_string()));
+        Ok(topic.to_string())
+    }
+
+    /// Switches active chain and notifies the dApp with `chainChanged` event
+    pub fn change_chain(&self, chain_id: u64) -> Result<(), Error> {
+        let mut state = self.state.lock().unwrap();
+        state.chain_id = chain_id;
+        let topic = state.session.clone().ok_or(Error::ConnectionClosed)?;
+        state.publish_event(&topic, "chainChanged", json!(chain_id))
+    }
+
+    /// Notifies the dApp with `accountsChanged` event
+    pub fn change_accounts(&self, accounts: Vec<Address>) -> Result<(), Error> {
+        let state = self.state.lock().unwrap();
+        let topic = state.session.clone().ok_or(Error::ConnectionClosed)?;
+        state.publish_event(&topic, "accountsChanged", json!(accounts))
+    }
+
+    /// Deletes the session on wallet's side
+    pub fn disconnect(&self) -> Result<(), Error> {
+        let mut state = self.state.lock().unwrap();
+        let topic = state.session.take().ok_or(Error::ConnectionClosed)?;
+        let request = Request::new(
+            "wc_sessionDelete",
+            json!({ "code": 6000, "message": "User disconnected." }),
+        );
+        state.publish(&topic, &request, tags::SESSION_DELETE)?;
+        state.emit(WalletEvent::SessionDeleted(topic));
+        Ok(())
+    }
+
+    /// Waits for next wallet event
+    pub async fn next_event(&self) -> Option<WalletEvent> {
+        self.events.lock().await.recv().await
+    }
+}
+
+impl Drop for HeadlessWallet {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+impl WalletState {
+    fn send(&self, payload: &impl serde::Serialize) {
+        if let Ok(text) = serde_json::to_string(payload) {
+            _ = self.outgoing.send(Message::Text(text));
+        }
+    }
+
+    fn emit(&self, event: WalletEvent) {
+        _ = self.events.send(event);
+    }
+
+    fn key(&self, topic: &str) -> Result<SymKey, Error> {
+        self.keys.get(topic).copied().ok_or_else(|| Error::UnknownTopic(topic.to_string()))
+    }
+
+    fn publish(&self, topic: &str, payload: &impl serde::Serialize, tag: u32) -> Result<(), Error> {
+        let message = self.key(topic)?.encrypt(&serde_json::to_string(payload)?)?;
+        self.send(&publish(topic, message, tag));
+        Ok(())
+    }
+
+    fn respond(&self, topic: &str, request: &Request, response: Response) -> Result<(), Error> {
+        let tag = tags::of(&request.method).map(tags::response).unwrap_or_default();
+        self.publish(topic, &response, tag)
+    }
+
+    fn publish_event(&self, topic: &str, name: &str, data: Value) -> Result<(), Error> {
+        let params = json!({
+            "event": { "name": name, "data": data },
+            "chainId": format!("eip155:{}", self.chain_id),
+        });
+        self.publish(topic, &Request::new("wc_sessionEvent", params), tags::SESSION_EVENT)
+    }
+
+    /// Namespaces approving supported methods the proposal asked for, or all of them if it
+    /// asked for none
+    fn namespaces(&self, proposal: &Value) -> Value {
+        let address = self.config.signer.address();
+        let chains = self.config.chains.iter().map(|c| format!("eip155:{c}")).collect::<Vec<_>>();
+        let accounts = chains.iter().map(|c| format!("{c}:{address:?}")).collect::<Vec<_>>();
+
+        let requested = ["requiredNamespaces", "optionalNamespaces"]
+            .iter()
+            .filter_map(|key| proposal[key]["eip155"]["methods"].as_array())
+            .flatten()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>();
+        let methods = METHODS
+            .into_iter()
+            .filter(|m| requested.is_empty() || requested.contains(m))
+            .collect::<Vec<_>>();
+
+        json!({
+            "eip155": {
+                "chains": chains,
+                "accounts": accounts,
+                "methods": methods,
+                "events": ["chainChanged", "accountsChanged"],
+            }
+        })
+    }
+
+    fn metadata(&self) -> Value {
+        json!({
+            "name": self.config.name,
+            "description": "Headless wallet for integration tests",
+            "url": "http://localhost",
+            "icons": [],
+        })
+    }
+
+    /// Approves or rejects session proposal received on pairing topic
+    fn propose(&mut self, topic: &str, request: &Request) -> Result<(), Error> {
+        self.emit(WalletEvent::SessionProposed(topic.to_string()));
+        if !self.config.approve_sessions {
+            self.emit(WalletEvent::SessionRejected(topic.to_string()));
+            let response = Response::error(request.id, USER_REJECTED, "User rejected.");
+            return self.respond(topic, request, response);
+        }
+
+        let proposer = request.params["proposer"]["publicKey"].as_str().unwrap_or_default();
+        let proposer = public_key_from_hex(proposer)?;
+        let (secret, public) = key_pair();
+        let public = hex::encode(public.as_bytes());
+
+        let response = Response::result(
+            request.id,
+            json!({ "relay": { "protocol": "irn" }, "responderPublicKey": public }),
+        );
+        self.respond(topic, request, response)?;
+
+        let key = SymKey::derive(&secret, &proposer);
+        let session = key.topic();
+        self.keys.insert(session.clone(), key);
+        self.send(&subscribe(&session));
+
+        let expiry = now() + SESSION_TTL_SECS;
+        let settle = Request::new(
+            "wc_sessionSettle",
+            json!({
+                "relay": { "protocol": "irn" },
+                "namespaces": self.namespaces(&request.params),
+                "controller": { "publicKey": public, "metadata": self.metadata() },
+                "expiry": expiry,
+            }),
+        );
+        self.publish(&session, &settle, tags::SESSION_SETTLE)?;
+        self.session = Some(session.clone());
+        self.expiry = Some(expiry);
+        self.emit(WalletEvent::SessionSettled(session));
+        Ok(())
+    }
+
+    /// Extends the session, up to its maximal lifetime from now
+    fn extend(&mut self, topic: &str, request: &Request) -> Result<(), Error> {
+        let response = match request.params["expiry"].as_u64() {
+            Some(expiry) if expiry <= now() + SESSION_TTL_SECS => {
+                self.expiry = Some(expiry);
+                self.emit(WalletEvent::SessionExtended(expiry));
+                Response::result(request.id, json!(true))
+            }
+            _ => Response::error(request.id, INVALID_EXTEND_REQUEST, "Invalid extend request."),
+        };
+        self.respond(topic, request, response)
+    }
+}
+
+async fn handle_message(state: &Shared, data: SubscriptionData) -> Result<(), Error> {
+    let key = state.lock().unwrap().key(&data.topic)?;
+    let request = match serde_json::from_str::<Payload>(&key.decrypt(&data.message)?)? {
+        Payload::Request(request) => request,
+        Payload::Response(response) => {
+            debug!("Wallet got response {response:?}");
+            return Ok(());
+        }
+    };
+    let topic = data.topic;
+
+    match request.method.as_str() {
+        "wc_sessionPropose" => state.lock().unwrap().propose(&topic, &request),
+        "wc_sessionExtend" => state.lock().unwrap().extend(&topic, &request),
+        "wc_sessionPing" | "wc_pairingPing" => {
+            let state = state.lock().unwrap();
+            state.respond(&topic, &request, Response::result(request.id, json!(true)))?;
+            state.emit(WalletEvent::Pinged(topic));
+            Ok(())
+        }
+        "wc_sessionRequest" => {
+            let method = request.params["request"]["method"].as_str().unwrap_or_default();
+            let params = request.params["request"]["params"].clone();
+            state.lock().unwrap().emit(WalletEvent::Request(method.to_string(), params.clone()));
+
+            let response = match session_request(state, method, params).await {
+                Ok(result) => Response::result(request.id, result),
+                Err((code, message)) => Response::error(request.id, code, &message),
+            };
+            state.lock().unwrap().respond(&topic, &request, response)
+        }
+        "wc_sessionDelete" | "wc_pairingDelete" => {
+            let mut state = state.lock().unwrap();
+            state.respond(&topic, &request, Response::result(request.id, json!(true)))?;
+            if state.session.as_deref() == Some(topic.as_str()) {
+                state.session = None;
+                state.emit(WalletEvent::SessionDeleted(topic));
+            }
+            Ok(())
+        }
+        _ => {
+            let state = state.lock().unwrap();
+            state.respond(&topic, &request, Response::result(request.id, json!(true)))
+        }
+    }
+}
+
+type RequestResult = Result<Value, (i64, String)>;
+
+async fn session_request(state: &Shared, method: &str, params: Value) -> RequestResult {
+    let (signer, chains, chain_id) = {
+        let state = state.lock().unwrap();
+        (state.config.signer.clone(), state.config.chains.clone(), state.chain_id)
+    };
+    let param = |index: usize| params.get(index).cloned().unwrap_or_default();
+    let failed = |err: &dyn std::fmt::Display| (USER_REJECTED, err.to_string());
+
+    match method {
+        "personal_sign" | "eth_sign" => {
+            let message = param(if method == "eth_sign" { 1 } else { 0 });
+            let message = message.as_str().unwrap_or_default();
+            let bytes = match message.strip_prefix("0x").map(hex::decode) {
+                Some(Ok(bytes)) => bytes,
+                _ => message.as_bytes().to_vec(),
+            };
+            let signature = signer.sign_message(bytes).await.map_err(|e| failed(&e))?;
+            Ok(json!(format!("0x{signature}")))
+        }
+        "eth_signTypedData_v4" => {
+            let data = match param(1) {
+                Value::String(data) => serde_json::from_str::<TypedData>(&data),
+                data => serde_json::from_value::<TypedData>(data),
+            }
+            .map_err(|e| failed(&e))?;
+            let signature = signer.sign_typed_data(&data).await.map_err(|e| failed(&e))?;
+            Ok(json!(format!("0x{signature}")))
+        }
+        "eth_sendTransaction" => {
+            let mut tx: TransactionRequest =
+                serde_json::from_value(param(0)).map_err(|e| failed(&e))?;
+            tx.chain_id = Some(chain_id.into());
+            tx.nonce = tx.nonce.or(Some(U256::zero()));
+            tx.gas = tx.gas.or(Some(21_000.into()));
+            tx.gas_price = tx.gas_price.or(Some(1_000_000_000.into()));
+
+            let tx = TypedTransaction::Legacy(tx);
+            let signature = signer
+                .with_chain_id(chain_id)
+                .sign_transaction(&tx)
+                .await
+                .map_err(|e| failed(&e))?;
+            let raw: Bytes = tx.rlp_signed(&signature);
+            Ok(json!(H256::from(keccak256(raw))))
+        }
+        "wallet_switchEthereumChain" => {
+            let requested = param(0)["chainId"].as_str().unwrap_or_default().to_string();
+            let chain = u64::from_str_radix(requested.trim_start_matches("0x"), 16)
+                .ok()
+                .filter(|c| chains.contains(c))
+                .ok_or((UNRECOGNIZED_CHAIN, format!("Unrecognized chain {requested}")))?;
+
+            let mut state = state.lock().unwrap();
+            state.chain_id = chain;
+            if let Some(topic) = state.session.clone() {
+                state
+                    .publish_event(&topic, "chainChanged", json!(chain))
+                    .map_err(|e| failed(&e))?;
+            }
+            Ok(Value::Null)
+        }
+        _ => Err((UNSUPPORTED_METHOD, format!("Unsupported method {method}"))),
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DappPeer, FakeRelay};
+    use ethers::types::Signature;
+
+    async fn connected() -> (FakeRelay, HeadlessWallet, DappPeer) {
+        let relay = FakeRelay::start().await.unwrap();
+        let wallet = HeadlessWallet::connect(&relay.url(), WalletConfig::default()).await.unwrap();
+        let mut dapp = DappPeer::connect(&relay.url()).await.unwrap();
+
+        let required =
+            json!({ "eip155": { "chains": ["eip155:1"], "methods": METHODS, "events": [] } });
+        let uri = dapp.propose(required, json!({})).await.unwrap();
+        wallet.pair(&uri).unwrap();
+        dapp.settle().await.unwrap();
+        (relay, wallet, dapp)
+    }
+
+    #[tokio::test]
+    async fn test_session_and_personal_sign() {
+        let (_relay, wallet, mut dapp) = connected().await;
+        assert_eq!(wallet.session(), dapp.session());
+
+        let params = json!(["hello", wallet.address()]);
+        let response = dapp.request(1, "personal_sign", params).await.unwrap();
+        let signature: Signature = response.result.unwrap().as_str().unwrap().parse().unwrap();
+        assert_eq!(signature.recover("hello").unwrap(), wallet.address());
+    }
+
+    #[tokio::test]
+    async fn test_ping_and_extend() {
+        let (_relay, wallet, mut dapp) = connected().await;
+
+        let response = dapp.call("wc_sessionPing", json!({})).await.unwrap();
+        assert_eq!(response.result, Some(json!(true)));
+
+        let expiry = now() + 60;
+        let response = dapp.call("wc_sessionExtend", json!({ "expiry": expiry })).await.unwrap();
+        assert_eq!(response.result, Some(json!(true)));
+        assert_eq!(wallet.expiry(), Some(expiry));
+
+        let too_late = now() + 2 * SESSION_TTL_SECS;
+        let response = dapp.call("wc_sessionExtend", json!({ "expiry": too_late })).await.unwrap();
+        assert_eq!(response.error.map(|e| e.code), Some(INVALID_EXTEND_REQUEST));
+    }
+}
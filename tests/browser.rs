@@ -0,0 +1,157 @@
+//! `Ethereum` connecting over WalletConnect in a browser, against relay and headless wallet of
+//! `fake-walletconnect` from `test-support`:
+//!
+//! ```sh
+//! CHAINS=1,5 cargo run --manifest-path test-support/Cargo.toml &
+//! wasm-pack test --headless --chrome -- --test browser
+//! ```
+#![cfg(target_arch = "wasm32")]
+
+use ethers::types::{
+    transaction::eip712::{Eip712, TypedData},
+    Address,
+};
+use ethers_web::{
+    connection::ConnectionState, Ethereum, EthereumBuilder, Event, EventKind, WalletType,
+};
+use futures::{
+    channel::mpsc::{unbounded, UnboundedReceiver},
+    future::{select, Either},
+    StreamExt,
+};
+use gloo::{
+    net::http::{Request, Response},
+    timers::future::TimeoutFuture,
+};
+use serde_json::json;
+use url::Url;
+use wasm_bindgen_futures::spawn_local;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const RELAY_URL: &str = "ws://127.0.0.1:9545";
+const CONTROL_URL: &str = "http://127.0.0.1:9546";
+/// Address of headless wallet's default key
+const WALLET_ADDRESS: &str = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266";
+const EVENT_TIMEOUT_MS: u32 = 10_000;
+
+fn builder() -> EthereumBuilder {
+    let mut builder = EthereumBuilder::new();
+    builder.walletconnect_id("browser-tests");
+    builder.wc_relay_url(Url::parse(RELAY_URL).unwrap());
+    builder
+}
+
+/// Calls headless wallet's control endpoint, returning the answer
+async fn control(path: &str, body: &str) -> String {
+    let request = Request::post(&format!("{CONTROL_URL}{path}")).body(body.to_string()).unwrap();
+    answer(path, request.send().await.unwrap()).await
+}
+
+/// Reads headless wallet's state from its control endpoint
+async fn query(path: &str) -> String {
+    answer(path, Request::get(&format!("{CONTROL_URL}{path}")).send().await.unwrap()).await
+}
+
+async fn answer(path: &str, response: Response) -> String {
+    let answer = response.text().await.unwrap();
+    assert!(response.ok(), "{path} failed: {answer}");
+    answer
+}
+
+/// Fetches events the way UI hooks do, on a clone of connected `Ethereum`
+fn listen(ethereum: &Ethereum) -> UnboundedReceiver<Event> {
+    let (events, received) = unbounded();
+    let ethereum = ethereum.clone();
+    spawn_local(async move {
+        while let Ok(event) = ethereum.next().await {
+            if let Some(event) = event {
+                if events.unbounded_send(event).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+    received
+}
+
+async fn expect(events: &mut UnboundedReceiver<Event>, kind: EventKind) -> Event {
+    loop {
+        let timeout = TimeoutFuture::new(EVENT_TIMEOUT_MS);
+        match select(events.next(), timeout).await {
+            Either::Left((Some(event), _)) if event.kind() == kind => return event,
+            Either::Left((Some(_), _)) => {}
+            Either::Left((None, _)) => panic!("Events ended before {kind:?}"),
+            Either::Right(_) => panic!("No {kind:?} in time"),
+        }
+    }
+}
+
+/// Connects to headless wallet, pairing it with uri of `Event::ConnectionWaiting`
+async fn connect(ethereum: &mut Ethereum) -> UnboundedReceiver<Event> {
+    ethereum.connect(WalletType::WalletConnect).await.unwrap();
+    let mut events = listen(ethereum);
+
+    let Event::ConnectionWaiting(pairing) = expect(&mut events, EventKind::ConnectionWaiting).await
+    else {
+        unreachable!()
+    };
+    control("/pair", &pairing.uri.unwrap()).await;
+    expect(&mut events, EventKind::Connected).await;
+    events
+}
+
+fn typed_data() -> TypedData {
+    serde_json::from_value(json!({
+        "types": {
+            "EIP712Domain": [{ "name": "name", "type": "string" }],
+            "Mail": [{ "name": "contents", "type": "string" }],
+        },
+        "primaryType": "Mail",
+        "domain": { "name": "ethers-web" },
+        "message": { "contents": "Hello from the browser" },
+    }))
+    .unwrap()
+}
+
+#[wasm_bindgen_test]
+async fn test_connect_sign_typed_data_and_disconnect() {
+    let mut ethereum = builder().build();
+    let mut events = connect(&mut ethereum).await;
+
+    let wallet: Address = WALLET_ADDRESS.parse().unwrap();
+    assert_eq!(ethereum.connection_state(), ConnectionState::Connected);
+    assert_eq!(ethereum.active_account(), Some(wallet));
+
+    let data = typed_data();
+    let signature = ethereum.sign_typed_data(data.clone(), &wallet).await.unwrap();
+    assert_eq!(signature.recover(data.encode_eip712().unwrap()).unwrap(), wallet);
+
+    control("/disconnect", "").await;
+    expect(&mut events, EventKind::Disconnected).await;
+    assert_eq!(ethereum.connection_state(), ConnectionState::Idle);
+}
+
+#[wasm_bindgen_test]
+async fn test_switch_network_through_wallet() {
+    let mut builder = builder();
+    builder.wc_required_namespace(
+        &["eip155:1"],
+        &["eth_signTypedData_v4", "personal_sign"],
+        &["chainChanged", "accountsChanged"],
+    );
+    builder.wc_optional_namespace(&["eip155:5"], &["wallet_switchEthereumChain"], &[]);
+    let mut ethereum = builder.build();
+    let mut events = connect(&mut ethereum).await;
+    assert_eq!(query("/chain").await, "1");
+
+    ethereum.switch_network(5).await.unwrap();
+    // Chain of the settled session may still be reported before the switched one
+    while expect(&mut events, EventKind::ChainIdChanged).await != Event::ChainIdChanged(Some(5)) {}
+    assert_eq!(query("/chain").await, "5");
+    assert_eq!(ethereum.connection_state(), ConnectionState::Connected);
+
+    ethereum.disconnect().await;
+    expect(&mut events, EventKind::Disconnected).await;
+}
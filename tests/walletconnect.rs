@@ -0,0 +1,131 @@
+//! Wire level checks of local relay and headless wallet from `test-support`, with the dApp side
+//! played by `DappPeer`. Pairing uris and settled sessions go through the types `Ethereum` uses,
+//! but `Ethereum` itself is not run here; it is driven against the same harness in a browser by
+//! `tests/browser.rs`.
+#![cfg(not(target_arch = "wasm32"))]
+
+use ethers::types::Signature;
+use ethers_web::walletconnect::pairing::PairingInfo;
+use ethers_web_test_support::{DappPeer, FakeRelay, HeadlessWallet, WalletConfig, WalletEvent};
+use serde_json::{json, Value};
+use url::Url;
+use walletconnect_client::{prelude::Metadata, WalletConnectState};
+
+const NOW: u64 = 1_700_000_000;
+
+async fn start(chains: Vec<u64>) -> (FakeRelay, HeadlessWallet, DappPeer) {
+    let relay = FakeRelay::start().await.unwrap();
+    let config = WalletConfig { chains, ..Default::default() };
+    let wallet = HeadlessWallet::connect(&relay.url(), config).await.unwrap();
+    let dapp = DappPeer::connect(&relay.url()).await.unwrap();
+    (relay, wallet, dapp)
+}
+
+/// Pairs the dApp with the wallet and waits for the session settlement
+async fn pair(wallet: &HeadlessWallet, dapp: &mut DappPeer) -> Value {
+    let required = json!({
+        "eip155": {
+            "chains": ["eip155:1"],
+            "methods": ["eth_sendTransaction", "eth_signTypedData_v4"],
+            "events": ["chainChanged", "accountsChanged"],
+        }
+    });
+    let uri = dapp.propose(required, json!({})).await.unwrap();
+
+    let pairing = PairingInfo::parse(&uri, NOW).unwrap();
+    assert!(!pairing.is_reused());
+    assert_eq!(wallet.pair(&uri).unwrap(), pairing.topic);
+
+    dapp.settle().await.unwrap()
+}
+
+async fn wait_for(wallet: &HeadlessWallet, expected: impl Fn(&WalletEvent) -> bool) {
+    while let Some(event) = wallet.next_event().await {
+        if expected(&event) {
+            return;
+        }
+    }
+    panic!("Wallet stopped before expected event");
+}
+
+fn metadata() -> Metadata {
+    Metadata::from("Test", "Test dApp", Url::parse("https://app.example.org").unwrap(), vec![])
+}
+
+#[tokio::test]
+async fn test_pairing_settles_session_readable_by_client() {
+    let (_relay, wallet, mut dapp) = start(vec![1, 5]).await;
+    let settle = pair(&wallet, &mut dapp).await;
+    assert_eq!(wallet.session(), dapp.session());
+
+    let state = json!({
+        "state": { "Connected": dapp.session() },
+        "keys": [],
+        "session": {
+            "relay": { "protocol": "irn" },
+            "namespaces": settle["namespaces"],
+            "requiredNamespaces": {},
+            "pairingTopic": null,
+            "proposer": { "publicKey": "", "metadata": metadata() },
+            "controller": settle["controller"],
+            "expiry": null,
+            "chainId": 1,
+        },
+    });
+    let state: WalletConnectState = serde_json::from_str(&state.to_string()).unwrap();
+    let namespaces = state.session.namespaces.unwrap();
+    assert_eq!(namespaces["eip155"].chains.as_ref().map(Vec::len), Some(2));
+    assert_eq!(namespaces["eip155"].accounts.as_ref().map(Vec::len), Some(2));
+}
+
+#[tokio::test]
+async fn test_signing_requests() {
+    let (_relay, wallet, mut dapp) = start(vec![1]).await;
+    pair(&wallet, &mut dapp).await;
+
+    let response = dapp.request(1, "personal_sign", json!(["0x68656c6c6f", wallet.address()]));
+    let signature = response.await.unwrap().result.unwrap();
+    let signature: Signature = signature.as_str().unwrap().parse().unwrap();
+    assert_eq!(signature.recover("hello").unwrap(), wallet.address());
+
+    let tx = json!([{ "from": wallet.address(), "to": wallet.address(), "value": "0x1" }]);
+    let hash = dapp.request(1, "eth_sendTransaction", tx).await.unwrap().result.unwrap();
+    assert_eq!(hash.as_str().map(str::len), Some(66));
+
+    let unsupported = dapp.request(1, "eth_getBalance", json!([])).await.unwrap();
+    assert!(unsupported.error.is_some());
+}
+
+#[tokio::test]
+async fn test_restored_session_keeps_working() {
+    let (relay, wallet, mut dapp) = start(vec![1, 5]).await;
+    pair(&wallet, &mut dapp).await;
+    let key = dapp.session_key().unwrap();
+    drop(dapp);
+
+    let mut dapp = DappPeer::resume(&relay.url(), key).await.unwrap();
+    let response = dapp.call("wc_sessionPing", json!({})).await.unwrap();
+    assert_eq!(response.result, Some(json!(true)));
+
+    wallet.change_chain(5).unwrap();
+    let event = dapp.next_request().await.unwrap();
+    assert_eq!(event.method, "wc_sessionEvent");
+    assert_eq!(event.params["event"]["name"], "chainChanged");
+    assert_eq!(event.params["chainId"], "eip155:5");
+}
+
+#[tokio::test]
+async fn test_disconnect_from_either_side() {
+    let (_relay, wallet, mut dapp) = start(vec![1]).await;
+    pair(&wallet, &mut dapp).await;
+    let session = dapp.session().unwrap();
+
+    dapp.disconnect().await.unwrap();
+    wait_for(&wallet, |e| e == &WalletEvent::SessionDeleted(session.clone())).await;
+    assert_eq!(wallet.session(), None);
+
+    pair(&wallet, &mut dapp).await;
+    wallet.disconnect().unwrap();
+    let delete = dapp.next_request().await.unwrap();
+    assert_eq!(delete.method, "wc_sessionDelete");
+}
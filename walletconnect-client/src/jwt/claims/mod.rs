@@ -0,0 +1,5 @@
+pub mod basic;
+mod verifiable;
+mod watch_event;
+mod watch_register;
+mod watch_unregister;
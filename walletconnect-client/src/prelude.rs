@@ -0,0 +1,3 @@
+pub use super::{
+    error::Error as WalletConnectError, event::Event, metadata::Metadata, WalletConnect,
+};